@@ -0,0 +1,188 @@
+//! Headless mode, writing a curve as SVG without opening a window
+
+use crate::dragon::{CurveFlags, DragonCurve, IoAdapter, SvgStyle, max_depth};
+use crate::terdragon::{self, TerDir, Terdragon};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+const USAGE: &str = "\
+Usage: dragons [--depth N] [--flip] [--levy] [--size SIZE] [--width WIDTH]
+               [--background RRGGBB] [--grid] [--rotate DEGREES]
+               [--markers RADIUS] [--terdragon] [--out FILE]
+
+Writes the curve as an SVG document instead of opening a window.
+The background is transparent unless a hex color is given.
+--grid draws the lattice the vertices lie on behind the curve.
+--rotate turns the curve clockwise around its start.
+--markers draws dots of the given radius at the start and end.
+--terdragon draws the terdragon instead, which only takes --depth and --size.
+The depth is capped at 16, or 10 for the terdragon, unless
+DRAGONS_MAX_SEGMENTS allows more.
+FILE defaults to -, which writes to stdout.";
+
+/// Options for a headless render
+struct Args {
+    depth: u8,
+    flags: CurveFlags,
+    size: f32,
+    width: f32,
+    background: Option<[u8; 3]>,
+    grid: bool,
+    rotation: f32,
+    markers: Option<f32>,
+    /// Draw the terdragon instead of the curve picked by `flags`
+    terdragon: bool,
+    out: String,
+}
+
+/// Parse the command-line arguments, not including the program name
+fn parse(args: &[String]) -> Result<Args, String> {
+    let mut out = Args {
+        depth: 0,
+        flags: CurveFlags::DRAGON,
+        size: crate::REFERENCE_SIZE,
+        width: 1.0,
+        background: None,
+        grid: false,
+        rotation: 0.0,
+        markers: None,
+        terdragon: false,
+        out: "-".to_string(),
+    };
+    // the cap depends on which curve it is, so it's checked once every argument is in
+    let mut depth = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+        match arg.as_str() {
+            "--depth" => depth = Some(value(arg)?),
+            "--size" => {
+                let v = value(arg)?;
+                out.size = v
+                    .parse()
+                    .ok()
+                    .filter(|&s: &f32| s > 0.0 && s.is_finite())
+                    .ok_or_else(|| format!("invalid size {v:?}"))?;
+            }
+            "--width" => {
+                let v = value(arg)?;
+                out.width = v
+                    .parse()
+                    .ok()
+                    .filter(|&w: &f32| w > 0.0 && w.is_finite())
+                    .ok_or_else(|| format!("invalid width {v:?}"))?;
+            }
+            "--background" => {
+                let v = value(arg)?;
+                out.background = Some(
+                    parse_color(v)
+                        .ok_or_else(|| format!("invalid color {v:?}, expected RRGGBB"))?,
+                );
+            }
+            "--rotate" => {
+                let v = value(arg)?;
+                out.rotation = v
+                    .parse()
+                    .ok()
+                    .filter(|r: &f32| r.is_finite())
+                    .ok_or_else(|| format!("invalid rotation {v:?}"))?;
+            }
+            "--markers" => {
+                let v = value(arg)?;
+                out.markers = Some(
+                    v.parse()
+                        .ok()
+                        .filter(|&r: &f32| r > 0.0 && r.is_finite())
+                        .ok_or_else(|| format!("invalid marker radius {v:?}"))?,
+                );
+            }
+            "--out" => out.out = value(arg)?.clone(),
+            "--flip" => out.flags |= CurveFlags::FLIP,
+            "--levy" => out.flags |= CurveFlags::LEVY,
+            "--grid" => out.grid = true,
+            "--terdragon" => out.terdragon = true,
+            _ => return Err(format!("unknown argument {arg:?}")),
+        }
+    }
+    if let Some(v) = depth {
+        let budget = crate::segment_budget();
+        let max = if out.terdragon {
+            terdragon::max_depth(budget)
+        } else {
+            max_depth(budget)
+        };
+        out.depth = v
+            .parse()
+            .ok()
+            .filter(|&d| d <= max)
+            .ok_or_else(|| format!("invalid depth {v:?}, expected 0 to {max}"))?;
+    }
+    Ok(out)
+}
+
+/// Parse a hex color like `ff8000`, with or without a leading `#`
+fn parse_color(s: &str) -> Option<[u8; 3]> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if s.len() != 6 || !s.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Write the curve described by `args` to its output
+fn render(args: &Args) -> io::Result<()> {
+    let w: Box<dyn Write> = if args.out == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(File::create(&args.out)?)
+    };
+    let mut adapter = IoAdapter::new(BufWriter::new(w));
+    if args.terdragon {
+        let mut curve = Terdragon::new(TerDir::EAST);
+        curve.set_depth(args.depth);
+        let res = curve.write_svg(args.size, &mut adapter);
+        adapter.finish(res)?;
+        return adapter.inner.flush();
+    }
+    let curve = DragonCurve::builder()
+        .flags(args.flags)
+        .depth(args.depth)
+        .build();
+    let style = SvgStyle {
+        background: args.background,
+        stroke_width: args.width,
+        grid: args.grid,
+        rotation: args.rotation,
+        markers: args.markers,
+        ..SvgStyle::default()
+    };
+    let res = curve.write_svg_styled(args.size, style, &mut adapter);
+    adapter.finish(res)?;
+    adapter.inner.flush()
+}
+
+/// Run headless with the given arguments, not including the program name, and return the exit code
+pub fn run(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return 0;
+    }
+    let args = match parse(args) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return 2;
+        }
+    };
+    match render(&args) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Failed to write {}: {err}", args.out);
+            1
+        }
+    }
+}
@@ -1,45 +1,157 @@
 #![allow(dead_code)]
 
 use std::cmp::Ordering;
-use std::collections::LinkedList;
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::FRAC_1_SQRT_2 as SCALE;
 use std::fmt::{self, Write};
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::Range;
+use std::path::Path;
 
 pub trait Draw {
     type Output;
 
     fn line(&mut self, x: f32, y: f32) -> Self::Output;
+    /// Move the current position by `(x, y)` without drawing anything
+    fn move_by(&mut self, x: f32, y: f32) -> Self::Output;
     fn horiz(&mut self, x: f32) -> Self::Output {
         self.line(x, 0.0)
     }
     fn vert(&mut self, y: f32) -> Self::Output {
         self.line(0.0, y)
     }
+    /// Quadratic Bézier curve with its control point at `(cx, cy)` and its end at `(x, y)`, both
+    /// relative to the current position. By default this is drawn straight to the end.
+    fn quad(&mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self::Output {
+        let _ = (cx, cy);
+        self.line(x, y)
+    }
 }
+/// [`Draw`] for renderers that collect what they draw instead of returning anything per segment.
+///
+/// Unlike `Draw` itself, this names its output, so different renderers can be picked at runtime
+/// behind a `&mut dyn DrawSink` or `Box<dyn DrawSink>`. Every `Draw<Output = ()>` implements it.
+pub trait DrawSink: Draw<Output = ()> {}
+impl<D: Draw<Output = ()> + ?Sized> DrawSink for D {}
 
 pub struct SvgPath<'a> {
     /// Underlying writer to write to
     pub writer: &'a mut dyn Write,
+    /// Decimal places for coordinates, or `None` to write them exactly
+    pub precision: Option<usize>,
 }
 impl Draw for SvgPath<'_> {
     type Output = fmt::Result;
 
     fn line(&mut self, x: f32, y: f32) -> fmt::Result {
-        write!(self.writer, "l{x} {y}")
+        let n = |v: f32| Num(v, self.precision);
+        write!(self.writer, " l{} {}", n(x), n(y))
+    }
+    fn move_by(&mut self, x: f32, y: f32) -> fmt::Result {
+        let n = |v: f32| Num(v, self.precision);
+        write!(self.writer, " m{} {}", n(x), n(y))
     }
     fn horiz(&mut self, x: f32) -> fmt::Result {
-        write!(self.writer, "h{x}")
+        write!(self.writer, " h{}", Num(x, self.precision))
     }
     fn vert(&mut self, y: f32) -> fmt::Result {
-        write!(self.writer, "v{y}")
+        write!(self.writer, " v{}", Num(y, self.precision))
+    }
+    fn quad(&mut self, cx: f32, cy: f32, x: f32, y: f32) -> fmt::Result {
+        let n = |v: f32| Num(v, self.precision);
+        write!(self.writer, " q{} {} {} {}", n(cx), n(cy), n(x), n(y))
+    }
+}
+
+/// Writes PostScript `rlineto` commands, flipping the y axis so the curve isn't mirrored
+pub struct PostScript<'a> {
+    /// Underlying writer to write to
+    pub writer: &'a mut dyn Write,
+    /// Decimal places for coordinates, or `None` to write them exactly
+    pub precision: Option<usize>,
+}
+impl Draw for PostScript<'_> {
+    type Output = fmt::Result;
+
+    fn line(&mut self, x: f32, y: f32) -> fmt::Result {
+        let n = |v: f32| Num(v, self.precision);
+        writeln!(self.writer, "{} {} rlineto", n(x), n(-y))
+    }
+    fn move_by(&mut self, x: f32, y: f32) -> fmt::Result {
+        let n = |v: f32| Num(v, self.precision);
+        writeln!(self.writer, "{} {} rmoveto", n(x), n(-y))
+    }
+}
+
+/// Writes the `-- (x,y)` steps of a TikZ path in absolute coordinates, flipping the y axis so the
+/// curve isn't mirrored
+pub struct TikzPath<'a> {
+    /// Underlying writer to write to
+    pub writer: &'a mut dyn Write,
+    /// Current position, in the same coordinates as the moves
+    pub x: f32,
+    pub y: f32,
+    /// Decimal places for coordinates, or `None` to write them exactly
+    pub precision: Option<usize>,
+}
+impl Draw for TikzPath<'_> {
+    type Output = fmt::Result;
+
+    fn line(&mut self, x: f32, y: f32) -> fmt::Result {
+        self.x += x;
+        self.y += y;
+        let n = |v: f32| Num(v, self.precision);
+        write!(self.writer, "\n  -- ({},{})", n(self.x), n(-self.y))
+    }
+    fn move_by(&mut self, x: f32, y: f32) -> fmt::Result {
+        self.x += x;
+        self.y += y;
+        let n = |v: f32| Num(v, self.precision);
+        write!(self.writer, "\n  ({},{})", n(self.x), n(-self.y))
+    }
+}
+
+/// Adapts an [`io::Write`] so it can be passed to exporters that take a [`fmt::Write`].
+///
+/// `fmt::Error` carries no information, so the underlying I/O error is kept here and can be
+/// recovered with [`IoAdapter::finish`].
+pub struct IoAdapter<W> {
+    /// Underlying writer to write to
+    pub inner: W,
+    error: Option<io::Error>,
+}
+impl<W: io::Write> IoAdapter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+    /// Convert the result of a formatting call into an I/O result, surfacing the original error
+    pub fn finish(&mut self, res: fmt::Result) -> io::Result<()> {
+        match (res, self.error.take()) {
+            (_, Some(err)) => Err(err),
+            (Ok(()), None) => Ok(()),
+            (Err(_), None) => Err(io::Error::other("formatter error")),
+        }
+    }
+}
+impl<W: io::Write> Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
     }
 }
 
 #[allow(dead_code)] // variants are constructed through transmutes
-#[derive(Debug, Clone, Copy)]
+/// Defaults to [`Dir::Np0`], pointing along `+x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Dir {
     Npp, // ++
+    #[default]
     Np0, // +0
     Npm, // +-
     N0m, // 0-
@@ -49,6 +161,18 @@ pub enum Dir {
     N0p, // 0+
 }
 impl Dir {
+    /// Every direction, in order of their values
+    pub const ALL: [Self; 8] = [
+        Self::Npp,
+        Self::Np0,
+        Self::Npm,
+        Self::N0m,
+        Self::Nmm,
+        Self::Nm0,
+        Self::Nmp,
+        Self::N0p,
+    ];
+
     #[inline(always)]
     pub const unsafe fn from_u8_unchecked(idx: u8) -> Self {
         unsafe { std::mem::transmute(idx) }
@@ -57,9 +181,14 @@ impl Dir {
     pub const fn from_u8(idx: u8) -> Self {
         unsafe { Self::from_u8_unchecked(idx % 8) }
     }
+    /// Whether this is one of the four diagonal directions
+    #[inline(always)]
+    pub fn is_diagonal(&self) -> bool {
+        (*self as u8).is_multiple_of(2)
+    }
     #[inline(always)]
     pub fn rotate(&self, by: u8) -> Self {
-        Self::from_u8(*self as u8 + by)
+        Self::from_u8(*self as u8 + by % 8)
     }
     #[inline(always)]
     pub fn right(&self) -> Self {
@@ -69,52 +198,604 @@ impl Dir {
     pub fn left(&self) -> Self {
         self.rotate(7)
     }
-    pub fn draw<D: Draw>(&self, out: &mut D, len: f32) -> D::Output {
+    /// Direction turned 180° around
+    #[inline(always)]
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+    /// Offset covered by a segment of unit length in this direction
+    pub const fn as_vec(&self) -> (f32, f32) {
+        DELTAS[*self as usize]
+    }
+    pub fn draw<D: Draw + ?Sized>(&self, out: &mut D, len: f32) -> D::Output {
+        let (x, y) = self.as_vec();
+        if y == 0.0 {
+            out.horiz(len * x)
+        } else if x == 0.0 {
+            out.vert(len * y)
+        } else {
+            out.line(len * x, len * y)
+        }
+    }
+    /// Offset of a segment in this direction on the integer lattice, where diagonals move one step
+    /// along each axis
+    fn offset(&self) -> (i32, i32) {
+        let (x, y) = self.as_vec();
+        let sign = |v: f32| (v > 0.0) as i32 - (v < 0.0) as i32;
+        (sign(x), sign(y))
+    }
+    /// Offset covered by a segment of length `len` in this direction
+    fn delta(&self, len: f32) -> (f32, f32) {
+        let (x, y) = self.as_vec();
+        (len * x, len * y)
+    }
+    /// Draw this segment with its corners rounded off where it turns from `prev` and into `next`.
+    ///
+    /// The straight part is shortened by `radius` at each turning end, and the turn into `next` is
+    /// drawn as a quadratic curve through where the corner would be. `radius` is clamped to half
+    /// of `len` so neighboring corners never overlap. Returns the outputs of the straight part and
+    /// of the corner, if there is one.
+    pub fn draw_rounded<D: Draw + ?Sized>(
+        &self,
+        prev: Option<Dir>,
+        next: Option<Dir>,
+        out: &mut D,
+        len: f32,
+        radius: f32,
+    ) -> (D::Output, Option<D::Output>) {
+        let radius = radius.clamp(0.0, len * 0.5);
+        let next = next.filter(|n| n != self);
+        let mut straight = len;
+        if prev.is_some_and(|p| p != *self) {
+            straight -= radius;
+        }
+        if next.is_some() {
+            straight -= radius;
+        }
+        let line = self.draw(out, straight);
+        let corner = next.map(|next| {
+            let (cx, cy) = self.delta(radius);
+            let (nx, ny) = next.delta(radius);
+            out.quad(cx, cy, cx + nx, cy + ny)
+        });
+        (line, corner)
+    }
+}
+/// Shows the direction as an arrow pointing the way it's drawn on screen, where `+y` is down
+impl fmt::Display for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Npp => "↘",
+            Self::Np0 => "→",
+            Self::Npm => "↗",
+            Self::N0m => "↑",
+            Self::Nmm => "↖",
+            Self::Nm0 => "←",
+            Self::Nmp => "↙",
+            Self::N0p => "↓",
+        })
+    }
+}
+
+/// Offset of a unit-length segment in each direction, indexed by `Dir as usize`. Axis-aligned
+/// directions have exactly one zero component, which is how [`Dir::draw`] picks `horiz` or `vert`.
+const DELTAS: [(f32, f32); 8] = [
+    (SCALE, SCALE),
+    (1.0, 0.0),
+    (SCALE, -SCALE),
+    (0.0, -1.0),
+    (-SCALE, -SCALE),
+    (-1.0, 0.0),
+    (-SCALE, SCALE),
+    (0.0, 1.0),
+];
+
+/// How the stored directions are turned into moves when a curve is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderStyle {
+    /// Every segment is drawn horizontally or vertically, as in the classic right-angle dragon.
+    ///
+    /// Curves made of diagonals come out rotated 45° clockwise about their start.
+    Sharp90,
+    /// Segments are drawn in the directions they're stored in, which is diagonal at every other
+    /// depth so that the endpoints stay put
+    #[default]
+    Diagonal45,
+}
+impl RenderStyle {
+    /// Direction a segment stored as `dir` is drawn in
+    pub fn orient(self, dir: Dir) -> Dir {
+        match self {
+            Self::Sharp90 if dir.is_diagonal() => dir.left(),
+            _ => dir,
+        }
+    }
+}
+
+/// Sequence of directions packed into 3 bits each, for keeping large curves around compactly
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedDirs {
+    bytes: Vec<u8>,
+    len: usize,
+}
+impl PackedDirs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Number of directions stored
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Append a direction to the end
+    pub fn push(&mut self, dir: Dir) {
+        let bit = self.len * 3;
+        if bit + 3 > self.bytes.len() * 8 {
+            self.bytes.push(0);
+        }
+        let word = (dir as u16) << (bit % 8);
+        self.bytes[bit / 8] |= word as u8;
+        if word > 0xff {
+            self.bytes[bit / 8 + 1] |= (word >> 8) as u8;
+        }
+        self.len += 1;
+    }
+    /// Direction at index `i`.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn get(&self, i: usize) -> Dir {
+        assert!(
+            i < self.len,
+            "index {i} out of bounds for {} directions",
+            self.len
+        );
+        let bit = i * 3;
+        let lo = self.bytes[bit / 8] as u16;
+        let hi = self.bytes.get(bit / 8 + 1).copied().unwrap_or(0) as u16;
+        Dir::from_u8(((lo | hi << 8) >> (bit % 8)) as u8 & 7)
+    }
+    /// Directions in order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Dir> + ExactSizeIterator + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+    /// Packed bytes, with the first direction in the lowest bits of the first byte
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// Read back `len` directions packed as by [`Self::as_bytes`].
+    ///
+    /// Bytes and bits past the last direction are ignored, but fewer bytes than it takes to hold
+    /// `len` directions is an error.
+    pub fn from_bytes(len: usize, bytes: &[u8]) -> Result<Self, ShortBuffer> {
+        let needed = len
+            .checked_mul(3)
+            .map_or(usize::MAX, |bits| bits.div_ceil(8));
+        if bytes.len() < needed {
+            return Err(ShortBuffer {
+                len,
+                needed,
+                found: bytes.len(),
+            });
+        }
+        let mut bytes = bytes[..needed].to_vec();
+        // clear anything past the end, so later pushes and comparisons only see real directions
+        let used = len * 3 % 8;
+        if let Some(last) = bytes.last_mut().filter(|_| used > 0) {
+            *last &= (1 << used) - 1;
+        }
+        Ok(Self { bytes, len })
+    }
+}
+
+/// Error from [`PackedDirs::from_bytes`] for a buffer too short to hold every direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortBuffer {
+    /// Number of directions asked for
+    pub len: usize,
+    /// Bytes it takes to hold them
+    pub needed: usize,
+    pub found: usize,
+}
+impl fmt::Display for ShortBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} directions need {} bytes, but only {} were given",
+            self.len, self.needed, self.found
+        )
+    }
+}
+impl std::error::Error for ShortBuffer {}
+impl FromIterator<Dir> for PackedDirs {
+    fn from_iter<I: IntoIterator<Item = Dir>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut out = Self {
+            bytes: Vec::with_capacity((iter.size_hint().0 * 3).div_ceil(8)),
+            len: 0,
+        };
+        for dir in iter {
+            out.push(dir);
+        }
+        out
+    }
+}
+
+/// Formats a coordinate with an optional number of decimal places, trimming trailing zeros
+#[derive(Clone, Copy)]
+pub(crate) struct Num(pub f32, pub Option<usize>);
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(prec) = self.1 else {
+            return write!(f, "{}", self.0);
+        };
+        let s = format!("{:.*}", prec, self.0);
+        let s = if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &s
+        };
+        f.write_str(if s == "-0" { "0" } else { s })
+    }
+}
+
+/// Units for the physical size of an SVG document
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SvgUnits {
+    #[default]
+    Px,
+    Mm,
+    Pt,
+}
+impl SvgUnits {
+    /// Suffix for lengths in these units
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Self::Px => "",
+            Self::Mm => "mm",
+            Self::Pt => "pt",
+        }
+    }
+}
+
+/// Decimal places used for coordinates in SVG output unless another precision is given
+pub const SVG_PRECISION: usize = 3;
+
+/// Color of a segment from its index, direction and midpoint, as passed to
+/// [`DragonCurve::write_svg_colored`]
+type SegmentColor<'a> = dyn FnMut(usize, Dir, (f32, f32)) -> [u8; 3] + 'a;
+
+/// Options for [`DragonCurve::write_svg_styled`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgStyle {
+    /// Units of the `width` and `height` attributes. The `viewBox` is always in user units.
+    pub units: SvgUnits,
+    /// Decimal places for path coordinates, or `None` to write them exactly
+    pub precision: Option<usize>,
+    pub render: RenderStyle,
+    /// Color of a rectangle behind the curve covering the whole `viewBox`, or `None` to leave the
+    /// background transparent
+    pub background: Option<[u8; 3]>,
+    /// Width of the stroke, in user units. The `viewBox` is padded by half of it so the stroke
+    /// isn't clipped.
+    pub stroke_width: f32,
+    /// Whether to draw faint gridlines behind the curve, as described in
+    /// [`DragonCurve::grid_spacing`], with the axes through its start a little darker
+    pub grid: bool,
+    /// Angle to turn the curve clockwise around its start, in degrees. The path itself keeps its
+    /// coordinates and gets a `transform`, and the `viewBox` fits the turned curve.
+    pub rotation: f32,
+    /// Dash pattern of the stroke, written as a `stroke-dasharray`
+    pub dash: StrokeDash,
+    /// Radius of the dots marking the start of the curve in green and its end in red, or `None`
+    /// to leave them out
+    pub markers: Option<f32>,
+}
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            units: SvgUnits::Px,
+            precision: Some(SVG_PRECISION),
+            render: RenderStyle::Diagonal45,
+            background: None,
+            stroke_width: 1.0,
+            grid: false,
+            rotation: 0.0,
+            dash: StrokeDash::Solid,
+            markers: None,
+        }
+    }
+}
+
+/// SVG `fill-rule`, deciding which parts of a self-intersecting path count as inside
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FillRule {
+    /// Anywhere the path winds around at all is filled
+    #[default]
+    NonZero,
+    /// Regions alternate between filled and empty each time the path is crossed
+    EvenOdd,
+}
+impl FillRule {
+    /// Value of the `fill-rule` property
+    pub fn as_str(self) -> &'static str {
         match self {
-            Self::Npp => out.line(len * SCALE, len * SCALE),
-            Self::Np0 => out.horiz(len),
-            Self::Npm => out.line(len * SCALE, len * -SCALE),
-            Self::N0m => out.vert(-len),
-            Self::Nmm => out.line(len * -SCALE, len * -SCALE),
-            Self::Nm0 => out.horiz(-len),
-            Self::Nmp => out.line(len * -SCALE, len * SCALE),
-            Self::N0p => out.vert(len),
+            Self::NonZero => "nonzero",
+            Self::EvenOdd => "evenodd",
         }
     }
-    fn write_svg(&self, w: &mut dyn Write, scale: f32) -> fmt::Result {
+}
+
+/// Dash pattern of a stroke, scaled with its width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StrokeDash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+impl StrokeDash {
+    /// Length of each dash and of the gap after it for a stroke `width` wide, or `None` for a solid
+    /// line. Strokes thinner than 1 get the same pattern as 1, so the dashes stay visible.
+    pub fn pattern(self, width: f32) -> Option<[f32; 2]> {
+        let width = width.max(1.0);
         match self {
-            Self::Npp => write!(w, " l{} {}", scale * SCALE, scale * SCALE),
-            Self::Np0 => write!(w, " h{}", scale),
-            Self::Npm => write!(w, " l{} {}", scale * SCALE, scale * -SCALE),
-            Self::N0m => write!(w, " v{}", -scale),
-            Self::Nmm => write!(w, " l{} {}", scale * -SCALE, scale * -SCALE),
-            Self::Nm0 => write!(w, " h{}", -scale),
-            Self::Nmp => write!(w, " l{} {}", scale * -SCALE, scale * SCALE),
-            Self::N0p => write!(w, " v{}", scale),
+            Self::Solid => None,
+            Self::Dashed => Some([width * 4.0, width * 2.0]),
+            Self::Dotted => Some([width, width]),
+        }
+    }
+}
+
+/// Fill of the path written by [`DragonCurve::write_svg_filled`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgFill {
+    /// Fill color as RGB
+    pub color: [u8; 3],
+    /// Fill opacity, from 0 to 1
+    pub opacity: f32,
+    pub rule: FillRule,
+}
+impl Default for SvgFill {
+    fn default() -> Self {
+        Self {
+            color: [0; 3],
+            opacity: 1.0,
+            rule: FillRule::NonZero,
+        }
+    }
+}
+
+/// Where a curve is placed when it's drawn: the point it starts at, the length of its segments,
+/// and the angle it's turned by, clockwise in radians
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub origin: (f32, f32),
+    pub scale: f32,
+    pub rotation: f32,
+}
+impl Transform {
+    /// Framing of `curve` in a `size`-sized square centered on `center`, turned around the center
+    /// by `rotation`.
+    ///
+    /// Unturned, the curve starts a quarter of the way in from the left and halfway down, with
+    /// segments as long as [`DragonCurve::step`], so its endpoints stay put at every depth. This
+    /// is how it's placed both in the window and in exported images.
+    pub fn framed(curve: &DragonCurve, center: (f32, f32), size: f32, rotation: f32) -> Self {
+        let (sin, cos) = rotation.sin_cos();
+        let reach = size * 0.25;
+        Self {
+            origin: (center.0 - reach * cos, center.1 - reach * sin),
+            scale: curve.step(size),
+            rotation,
         }
     }
+    /// Position of the point `(x, y)` in units of segments, relative to the start of the curve
+    pub fn apply(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        (
+            self.origin.0 + self.scale * (x * cos - y * sin),
+            self.origin.1 + self.scale * (x * sin + y * cos),
+        )
+    }
+}
+
+/// Pen that only tracks its position, used to walk the vertices of a curve
+pub(crate) struct Pen {
+    pub x: f32,
+    pub y: f32,
+}
+impl Draw for Pen {
+    type Output = ();
+
+    fn line(&mut self, x: f32, y: f32) {
+        self.x += x;
+        self.y += y;
+    }
+    fn move_by(&mut self, x: f32, y: f32) {
+        self.line(x, y)
+    }
 }
 
 bitflags::bitflags! {
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    /// Variant of the curve to generate, defaulting to [`CurveFlags::DRAGON`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct CurveFlags: u8 {
+        /// Heighway dragon, folding alternately left and right
         const DRAGON = 0b00;
         const NONE = 0b00;
+        /// Lévy C curve, folding every segment to the same side
         const LEVY = 0b01;
+        /// Swap every left and right fold, including the turn of the first segment.
+        ///
+        /// This is the mirror image of the unflipped curve, reflected across the line between its
+        /// endpoints, so it also covers a clockwise versus counterclockwise turn convention.
         const FLIP = 0b10;
+        /// The segments were edited directly, as by [`DragonCurve::append`], so they no longer
+        /// follow from the depth and the other flags.
+        ///
+        /// [`DragonCurve::set_depth`] leaves these curves alone, and their start is just their
+        /// first segment. Serializing one only keeps the parameters, not the edited segments, so
+        /// it can't be deserialized again.
+        const CUSTOM = 0b100;
+    }
+}
+
+/// Error from [`DragonCurve::from_folds`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidFold {
+    /// A character other than `L` or `R`, at a byte index into the fold string
+    Char { index: usize, found: char },
+    /// More folds than fit in the segment budget
+    TooMany { folds: usize, budget: usize },
+}
+impl fmt::Display for InvalidFold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Char { index, found } => {
+                write!(
+                    f,
+                    "invalid fold {found:?} at index {index}, expected L or R"
+                )
+            }
+            Self::TooMany { folds, budget } => write!(
+                f,
+                "{folds} folds have more segments than the budget of {budget}, which allows up to {}",
+                max_depth(budget)
+            ),
+        }
+    }
+}
+impl std::error::Error for InvalidFold {}
+
+/// Error from [`DragonCurve::from_svg_path`], with byte indices into the path data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvalidPath {
+    /// A command other than an initial `M`, `l`, `h`, `v`, or a final `M` or `Z`
+    Command { index: usize, found: char },
+    /// A missing or malformed number
+    Number { index: usize },
+    /// A move that isn't one of the eight directions at the length of the first one, or that's
+    /// diagonal when the first one is axis-aligned or the other way around
+    Delta { index: usize, x: f32, y: f32 },
+    /// The path has no segments at all
+    Empty,
+}
+impl fmt::Display for InvalidPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Command { index, found } => {
+                write!(f, "unexpected command {found:?} at index {index}")
+            }
+            Self::Number { index } => write!(f, "expected a number at index {index}"),
+            Self::Delta { index, x, y } => write!(
+                f,
+                "move ({x}, {y}) at index {index} doesn't match any direction"
+            ),
+            Self::Empty => f.write_str("path has no segments"),
+        }
+    }
+}
+impl std::error::Error for InvalidPath {}
+
+/// Error from [`DragonCurve::try_set_depth`] for a depth with more segments than the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManySegments {
+    pub depth: u8,
+    /// Most segments the curve was allowed to have
+    pub budget: usize,
+}
+impl fmt::Display for TooManySegments {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "depth {} has more segments than the budget of {}, which allows up to depth {}",
+            self.depth,
+            self.budget,
+            max_depth(self.budget)
+        )
+    }
+}
+impl std::error::Error for TooManySegments {}
+
+/// Deepest curve that fits in `budget` segments.
+///
+/// Each level doubles the segment count, so this is `log2(budget)` rounded down, and a budget of
+/// 0 still allows the single segment at depth 0.
+pub fn max_depth(budget: usize) -> u8 {
+    budget.max(1).ilog2() as u8
+}
+
+/// Summary of a curve's derived values, returned by [`DragonCurve::info`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveInfo {
+    /// Number of segments
+    pub segments: usize,
+    /// Number of vertices, including the final point
+    pub vertices: usize,
+    /// Bounding box of the vertices, as `(min_x, min_y, max_x, max_y)`
+    pub bounds: (f32, f32, f32, f32),
+    /// Mean position of the vertices
+    pub centroid: (f32, f32),
+    /// Total length of the path
+    pub length: f32,
+    /// Width of the bounding box over its height, or `NaN` if it has no height, as for the
+    /// horizontal segment of a curve started along `+x` at depth 0
+    pub aspect_ratio: f32,
+    /// Number of places where consecutive segments change direction
+    pub turns: usize,
+    /// Number of segments going in each direction, indexed by `Dir as usize`
+    pub histogram: [usize; 8],
+}
+
+/// Stroke of a single depth in [`DragonCurve::write_svg_layers`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerStyle {
+    /// Stroke color as RGB
+    pub color: [u8; 3],
+    /// Stroke opacity, from 0 to 1
+    pub opacity: f32,
+}
+impl LayerStyle {
+    /// Black, getting more opaque with depth so the last of `max_depth + 1` layers is solid
+    pub fn fade(depth: u8, max_depth: u8) -> Self {
+        Self {
+            color: [0; 3],
+            opacity: (depth as f32 + 1.0) / (max_depth as f32 + 1.0),
+        }
     }
 }
 
+/// Segment count from which folds are split across threads with the `rayon` feature. Below this
+/// the overhead costs more than it saves.
+#[cfg(feature = "rayon")]
+const PAR_FOLD_THRESHOLD: usize = 1 << 14;
+
+/// With the `serde` feature, only the starting direction, depth, and flags are serialized, and the
+/// segments are regenerated when deserializing. Deserializing fails for depths past
+/// [`DESERIALIZE_BUDGET`] and for [`CurveFlags::CUSTOM`] curves, which can't be regenerated.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "CurveParams", try_from = "CurveParams")
+)]
 pub struct DragonCurve {
-    list: LinkedList<Dir>,
+    list: Vec<Dir>,
     depth: u8,
     flags: CurveFlags,
 }
 impl DragonCurve {
+    /// Start building a curve, by default a depth 0 dragon starting along `+x`
+    pub fn builder() -> DragonCurveBuilder {
+        DragonCurveBuilder::default()
+    }
     pub fn new(start: Dir, flags: CurveFlags) -> Self {
         Self {
-            list: LinkedList::from([start]),
+            list: vec![start],
             depth: 0,
             flags,
         }
@@ -124,64 +805,332 @@ impl DragonCurve {
             *elem = elem.rotate(by);
         }
     }
+    /// Turns between consecutive segments, in 45° steps clockwise from 0 to 7
+    pub fn turns(&self) -> impl Iterator<Item = u8> + '_ {
+        self.list
+            .iter()
+            .zip(self.list.iter().skip(1))
+            .map(|(&a, &b)| (b as u8 + 8 - a as u8) % 8)
+    }
+    /// Net turning over the whole curve in 45° steps, with each turn taken between -3 and 4
+    pub fn total_turning(&self) -> i32 {
+        self.turns()
+            .map(|t| if t > 4 { t as i32 - 8 } else { t as i32 })
+            .sum()
+    }
+    /// Rotate the curve so that its first segment points in the given direction.
+    ///
+    /// This does nothing if the curve has no segments.
     pub fn rotate_to(&mut self, to: Dir) {
-        let by = *self.list.front().unwrap() as u8 + 8 - to as u8;
-        self.rotate_by(by);
+        let Some(&front) = self.list.first() else {
+            return;
+        };
+        self.rotate_by(to as u8 + 8 - front as u8);
+    }
+    /// Rotation from the starting direction to the first segment at the current depth.
+    ///
+    /// Every expansion turns the first segment left (or right, if the curve is flipped), so at
+    /// depth `n` the front of the list is the starting direction rotated by `n` steps.
+    fn front_rotation(&self) -> u8 {
+        let by = self.depth % 8;
+        if self.flags.contains(CurveFlags::CUSTOM) {
+            0
+        } else if self.flags.contains(CurveFlags::FLIP) {
+            by
+        } else {
+            (8 - by) % 8
+        }
+    }
+    /// Switch to different flags, keeping the start and depth.
+    ///
+    /// Toggling only [`CurveFlags::FLIP`] mirrors the existing segments across the line between
+    /// the endpoints, which swaps every fold without changing anything else, so it's done in place.
+    /// Any other change regenerates the curve from scratch, including dropping
+    /// [`CurveFlags::CUSTOM`], since every fold depends on whether it's a dragon or a Lévy C curve.
+    pub fn set_flags(&mut self, flags: CurveFlags) {
+        let changed = self.flags ^ flags;
+        if changed.is_empty() {
+            return;
+        }
+        if changed == CurveFlags::FLIP {
+            // directions are mirrored about the starting direction, which points from one
+            // endpoint to the other
+            let axis = self.start() as u8 * 2;
+            for elem in &mut self.list {
+                *elem = Dir::from_u8(axis + 8 - *elem as u8);
+            }
+            self.flags = flags;
+        } else {
+            *self = Self::builder()
+                .start(self.start())
+                .flags(flags - CurveFlags::CUSTOM)
+                .depth(self.depth)
+                .build();
+        }
+    }
+    /// Reverse the curve in place, so it traces the same segments from its end back to its start.
+    ///
+    /// A reversed Lévy C curve is the flipped one started the other way, so this toggles
+    /// [`CurveFlags::FLIP`] for those and [`Self::start`] and [`Self::set_depth`] keep working. The
+    /// reversed dragon past depth 0 isn't any dragon: it still grows into the reverse of the deeper
+    /// curve, but shrinking it or asking for its start no longer matches the original.
+    pub fn reverse(&mut self) {
+        self.list.reverse();
+        for elem in &mut self.list {
+            *elem = elem.opposite();
+        }
+        if self.flags.contains(CurveFlags::LEVY) {
+            self.flags.toggle(CurveFlags::FLIP);
+        }
+    }
+    /// Splice `other` onto the end of this curve, turned so its first segment carries on in the
+    /// direction of this curve's last one.
+    ///
+    /// The result is no longer a curve of any depth, so it's marked [`CurveFlags::CUSTOM`] and
+    /// keeps this curve's depth only for reference.
+    pub fn append(&mut self, other: &DragonCurve) {
+        let by = match (self.list.last(), other.list.first()) {
+            (Some(&last), Some(&first)) => last as u8 + 8 - first as u8,
+            _ => 0,
+        };
+        self.list.extend(other.segments().map(|dir| dir.rotate(by)));
+        self.flags |= CurveFlags::CUSTOM;
+    }
+    /// Direction the curve was started with, i.e. its only segment at depth 0
+    pub fn start(&self) -> Dir {
+        self.list[0].rotate(8 - self.front_rotation())
+    }
+    /// Re-orient the whole curve so that it starts in the given direction
+    pub fn set_start(&mut self, dir: Dir) {
+        self.rotate_to(dir.rotate(self.front_rotation()));
+    }
+    /// Fold every segment in two, using `next` as scratch space.
+    ///
+    /// The first segment folds to the right if `right` is set, and the rest alternate sides if
+    /// `alternate` is set or all fold the same way otherwise. This doesn't update the depth.
+    fn fold(&mut self, mut right: bool, alternate: bool, next: &mut Vec<Dir>) {
+        #[cfg(feature = "rayon")]
+        if self.list.len() >= PAR_FOLD_THRESHOLD {
+            use rayon::prelude::*;
+            // the side each segment folds to only depends on its index, so every pair can be
+            // written independently
+            next.clear();
+            next.resize(self.list.len() * 2, Dir::Np0);
+            next.par_chunks_exact_mut(2)
+                .zip(self.list.par_iter())
+                .enumerate()
+                .for_each(|(i, (pair, dir))| {
+                    let right = right ^ (alternate && i % 2 == 1);
+                    pair.copy_from_slice(&if right {
+                        [dir.left(), dir.right()]
+                    } else {
+                        [dir.right(), dir.left()]
+                    });
+                });
+            std::mem::swap(&mut self.list, next);
+            return;
+        }
+        next.clear();
+        for dir in &self.list {
+            if right {
+                next.extend([dir.left(), dir.right()]);
+            } else {
+                next.extend([dir.right(), dir.left()]);
+            }
+            right ^= alternate;
+        }
+        std::mem::swap(&mut self.list, next);
+    }
+    /// Build a curve by folding a strip in the given order, like a generalized paperfolding
+    /// sequence.
+    ///
+    /// Each `R` or `L` folds every segment in two, with the first one turning right or left and
+    /// the rest alternating, so `"RRR"` is the depth 3 dragon and `"LLL"` is the same curve with
+    /// [`CurveFlags::FLIP`]. Lévy C folds never alternate, so they aren't expressible here.
+    ///
+    /// The curve gets `FLIP` if every fold is `L`. Mixed sequences don't match any flags, so
+    /// they're [`CurveFlags::CUSTOM`]. Like [`Self::try_set_depth`], this fails without folding
+    /// anything if the curve would have more than `budget` segments.
+    pub fn from_folds(start: Dir, folds: &str, budget: usize) -> Result<Self, InvalidFold> {
+        let mut sides = Vec::with_capacity(folds.len());
+        for (index, found) in folds.char_indices() {
+            match found {
+                'R' => sides.push(true),
+                'L' => sides.push(false),
+                _ => return Err(InvalidFold::Char { index, found }),
+            }
+        }
+        if sides.len() > max_depth(budget) as usize {
+            return Err(InvalidFold::TooMany {
+                folds: sides.len(),
+                budget,
+            });
+        }
+        let flags = if sides.iter().all(|&r| r) {
+            CurveFlags::DRAGON
+        } else if sides.iter().all(|&r| !r) {
+            CurveFlags::FLIP
+        } else {
+            CurveFlags::CUSTOM
+        };
+        let mut curve = Self::new(start, flags);
+        let mut next = Vec::with_capacity(1 << sides.len());
+        for &right in &sides {
+            curve.fold(right, true, &mut next);
+        }
+        curve.depth = sides.len() as u8;
+        Ok(curve)
+    }
+    /// Read back the `d` attribute of a path written by [`Self::write_svg`], [`Self::path_data`],
+    /// or any of the other SVG exporters that only draw straight segments. The rounded corners of
+    /// [`Self::write_svg_rounded`] can't be read back.
+    ///
+    /// The first segment sets the step, and every other one has to match one of the eight
+    /// directions at that length, to within rounding. Generated curves are either all diagonal or
+    /// all axis-aligned, so paths that mix the two are rejected. The depth and flags can't be
+    /// recovered, so the curve is [`CurveFlags::CUSTOM`], with the depth it would have if every
+    /// segment came from folding, so that [`Self::step`] frames it the same way as it was exported.
+    pub fn from_svg_path(d: &str) -> Result<Self, InvalidPath> {
+        let bytes = d.as_bytes();
+        let mut pos = 0;
+        let skip = |pos: &mut usize| {
+            while *pos < bytes.len() && (bytes[*pos].is_ascii_whitespace() || bytes[*pos] == b',') {
+                *pos += 1;
+            }
+        };
+        let number = |pos: &mut usize| {
+            skip(pos);
+            let start = *pos;
+            while *pos < bytes.len()
+                && (bytes[*pos].is_ascii_digit()
+                    || matches!(bytes[*pos], b'.' | b'e' | b'E')
+                    || (matches!(bytes[*pos], b'-' | b'+')
+                        && (*pos == start || matches!(bytes[*pos - 1], b'e' | b'E'))))
+            {
+                *pos += 1;
+            }
+            d[start..*pos]
+                .parse::<f32>()
+                .map_err(|_| InvalidPath::Number { index: start })
+        };
+        let mut list = Vec::new();
+        let mut step = None::<f32>;
+        let (mut started, mut ended) = (false, false);
+        loop {
+            skip(&mut pos);
+            let Some(&cmd) = bytes.get(pos) else {
+                break;
+            };
+            let index = pos;
+            let found = d[pos..].chars().next().unwrap_or_default();
+            pos += 1;
+            match cmd {
+                b'M' if !started => {
+                    number(&mut pos)?;
+                    number(&mut pos)?;
+                    started = true;
+                }
+                // the exporters move back to the start once they're done
+                b'M' if !list.is_empty() && !ended => {
+                    number(&mut pos)?;
+                    number(&mut pos)?;
+                    ended = true;
+                }
+                b'Z' if !list.is_empty() && !ended => ended = true,
+                b'l' | b'h' | b'v' if started && !ended => loop {
+                    let at = pos;
+                    let (x, y) = match cmd {
+                        b'l' => (number(&mut pos)?, number(&mut pos)?),
+                        b'h' => (number(&mut pos)?, 0.0),
+                        _ => (0.0, number(&mut pos)?),
+                    };
+                    let len = *step.get_or_insert(x.hypot(y));
+                    let tol = len * 0.01 + 1e-3;
+                    let first = list.first().map(Dir::is_diagonal);
+                    let dir = (0..8).map(Dir::from_u8).find(|dir| {
+                        let (dx, dy) = dir.delta(len);
+                        (dx - x).abs() <= tol
+                            && (dy - y).abs() <= tol
+                            && first.is_none_or(|diag| diag == dir.is_diagonal())
+                    });
+                    list.push(dir.ok_or(InvalidPath::Delta { index: at, x, y })?);
+                    // numbers can keep coming without repeating the command
+                    skip(&mut pos);
+                    if !bytes
+                        .get(pos)
+                        .is_some_and(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.'))
+                    {
+                        break;
+                    }
+                },
+                _ => return Err(InvalidPath::Command { index, found }),
+            }
+        }
+        if list.is_empty() {
+            return Err(InvalidPath::Empty);
+        }
+        let depth = list.len().ilog2() as u8;
+        Ok(Self {
+            list,
+            depth,
+            flags: CurveFlags::CUSTOM,
+        })
     }
+    /// Change the depth, folding every segment in two for each level deeper or keeping just the
+    /// start of the curve for each level shallower.
+    ///
+    /// With the `rayon` feature, folds of long curves are split across threads. This doesn't check
+    /// how much the new depth allocates, see [`Self::try_set_depth`] for that.
     pub fn set_depth(&mut self, depth: u8) {
+        if self.flags.contains(CurveFlags::CUSTOM) {
+            return;
+        }
         match self.depth.cmp(&depth) {
             Ordering::Equal => {}
             Ordering::Less => {
+                let mut next = Vec::with_capacity(1 << depth);
+                let right = !self.flags.contains(CurveFlags::FLIP);
+                let alternate = !self.flags.contains(CurveFlags::LEVY);
                 for _ in self.depth..depth {
-                    let mut cursor = self.list.cursor_front_mut();
-                    let mut right = true;
-                    while let Some(dir) = cursor.current() {
-                        let new = if (self.flags.contains(CurveFlags::LEVY) || right)
-                            ^ self.flags.contains(CurveFlags::FLIP)
-                        {
-                            let d2 = dir.right();
-                            *dir = dir.left();
-                            d2
-                        } else {
-                            let d2 = dir.left();
-                            *dir = dir.right();
-                            d2
-                        };
-                        cursor.move_next();
-                        cursor.insert_before(new);
-                        right = !right;
-                    }
+                    self.fold(right, alternate, &mut next);
                 }
             }
             Ordering::Greater => {
-                if depth == 0 {
-                    let mut cursor = self.list.cursor_front_mut();
-                    let front = cursor.current().unwrap();
-                    *front = front.rotate(if self.flags.contains(CurveFlags::FLIP) { 8u8.wrapping_sub(self.depth) } else { self.depth });
-                    cursor.split_after();
-                } else {
-                    let new_len = 1 << depth;
-                    let mut idx = 0;
-                    let mut rot = self.depth - depth;
-                    if self.flags.contains(CurveFlags::FLIP) {
-                        rot = 8u8.wrapping_sub(rot);
-                    }
-                    let mut cursor = self.list.cursor_front_mut();
-                    while let Some(elem) = cursor.current() {
-                        *elem = elem.rotate(rot);
-                        idx += 1;
-                        if idx == new_len {
-                            cursor.split_after();
-                            break;
-                        } else {
-                            cursor.move_next();
-                        }
-                    }
+                // the first half of a curve is the curve one level shallower with every segment
+                // turned a step, left unless it's flipped, so turn them back once per level
+                let mut rot = (self.depth - depth) % 8;
+                if self.flags.contains(CurveFlags::FLIP) {
+                    rot = (8 - rot) % 8;
+                }
+                self.list.truncate(1 << depth);
+                for elem in &mut self.list {
+                    *elem = elem.rotate(rot);
                 }
             }
         }
         self.depth = depth;
     }
+    /// Change the depth like [`Self::set_depth`], unless the curve would have more than `budget`
+    /// segments, in which case it's left as it was
+    pub fn try_set_depth(&mut self, depth: u8, budget: usize) -> Result<(), TooManySegments> {
+        if depth > max_depth(budget) && !self.flags.contains(CurveFlags::CUSTOM) {
+            return Err(TooManySegments { depth, budget });
+        }
+        self.set_depth(depth);
+        Ok(())
+    }
+    /// Go back to the single segment at depth 0, keeping the flags and starting direction.
+    ///
+    /// For generated curves this is the same as `set_depth(0)`. Custom curves can't change depth,
+    /// but this still cuts them down to their first segment. The list keeps its allocation.
+    pub fn reset(&mut self) {
+        if !self.list.is_empty() {
+            let start = self.start();
+            self.list.truncate(1);
+            self.list[0] = start;
+        }
+        self.depth = 0;
+    }
     /// Depth of the curve
     pub fn depth(&self) -> u8 {
         self.depth
@@ -190,30 +1139,2419 @@ impl DragonCurve {
     pub fn len(&self) -> usize {
         self.list.len()
     }
-    pub fn list(&self) -> &LinkedList<Dir> {
+    /// Whether the curve has no segments, which never happens for curves built with [`Self::new`]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+    /// Number of vertices, including the final point, so always one more than [`Self::len`]
+    pub fn vertex_count(&self) -> usize {
+        self.list.len() + 1
+    }
+    /// Directions of the segments in order, from the start of the curve to its end.
+    ///
+    /// The iterator can also be walked backwards, from the end of the curve to its start.
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = Dir> + ExactSizeIterator + '_ {
+        self.list.iter().copied()
+    }
+    /// Direction of the segment at `index`, or `None` if it's past the end of the curve
+    pub fn segment_at(&self, index: usize) -> Option<Dir> {
+        self.list.get(index).copied()
+    }
+    /// Directions the segments are drawn in with the given style, in order
+    pub fn segments_styled(
+        &self,
+        style: RenderStyle,
+    ) -> impl DoubleEndedIterator<Item = Dir> + ExactSizeIterator + '_ {
+        self.segments().map(move |dir| style.orient(dir))
+    }
+    /// Copy of the segments packed into 3 bits each
+    pub fn to_packed(&self) -> PackedDirs {
+        self.segments().collect()
+    }
+    /// Underlying list of segments.
+    ///
+    /// Deprecated: use [`Self::segments`] instead, which doesn't depend on how they're stored.
+    pub fn list(&self) -> &[Dir] {
         &self.list
     }
     pub fn flags(&self) -> CurveFlags {
         self.flags
     }
-    pub fn write_svg(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
-        let mut step = size / (1 << (self.depth / 2) + 1) as f32;
+    /// Length of a single segment when the curve is drawn in a `size`-sized square.
+    ///
+    /// Each level shrinks the segments by a factor of `√2` relative to the distance between the
+    /// endpoints, so this keeps them at `(size / 4, size / 2)` and `(3 * size / 4, size / 2)` at
+    /// every depth. At depth 0 that's a single horizontal segment across the middle of the square.
+    pub fn step(&self, size: f32) -> f32 {
+        // divide by 2^(depth / 2 + 1): the chord is half the square, and every two levels halve it
+        let mut step = size / (1 << ((self.depth / 2) + 1)) as f32;
         if self.depth & 1 != 0 {
             step *= SCALE;
         }
-        let start = format!("{} {}", size * 0.25, size * 0.5);
-        write!(
-            w,
-            r#"<svg width="{size}" height="{size}" xmlns="http://www.w3.org/2000/svg"><path style="stroke:black;stroke-width:1;fill:none" d="M{start}"#
-        )?;
-        for p in &self.list {
-            p.write_svg(w, step)?;
+        step
+    }
+    /// Summary of derived values for the curve drawn in a `size`-sized square, computed in a single walk
+    pub fn info(&self, size: f32) -> CurveInfo {
+        let step = self.step(size);
+        let mut pen = Pen {
+            x: size * 0.25,
+            y: size * 0.5,
+        };
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (pen.x, pen.y, pen.x, pen.y);
+        let (mut sum_x, mut sum_y) = (pen.x, pen.y);
+        let mut turns = 0;
+        let mut histogram = [0; 8];
+        let mut last = None;
+        for &dir in &self.list {
+            dir.draw(&mut pen, step);
+            min_x = min_x.min(pen.x);
+            min_y = min_y.min(pen.y);
+            max_x = max_x.max(pen.x);
+            max_y = max_y.max(pen.y);
+            sum_x += pen.x;
+            sum_y += pen.y;
+            histogram[dir as usize] += 1;
+            if last.is_some_and(|l| l != dir as u8) {
+                turns += 1;
+            }
+            last = Some(dir as u8);
+        }
+        let vertices = self.vertex_count();
+        let height = max_y - min_y;
+        CurveInfo {
+            segments: self.list.len(),
+            vertices,
+            bounds: (min_x, min_y, max_x, max_y),
+            centroid: (sum_x / vertices as f32, sum_y / vertices as f32),
+            length: self.list.len() as f32 * step,
+            aspect_ratio: if height > 0.0 {
+                (max_x - min_x) / height
+            } else {
+                f32::NAN
+            },
+            turns,
+            histogram,
         }
-        write!(w, r#" M{start}"/></svg>"#)
     }
-}
-impl PartialEq for DragonCurve {
-    fn eq(&self, other: &Self) -> bool {
-        self.depth == other.depth && self.flags == other.flags
+    /// Draw just the segments in `range`, moving past the ones before it first so they land where
+    /// they would in the whole curve. Anything past the end of the curve is left out.
+    ///
+    /// Renderers that count segments as they go get no segments for the moved-past part, so they
+    /// need to start counting at `range.start` themselves.
+    pub fn draw_range(&self, out: &mut dyn DrawSink, step: f32, range: Range<usize>) {
+        self.draw_range_styled(out, step, RenderStyle::Diagonal45, range);
+    }
+    /// Same as [`Self::draw_range`], with the segments drawn in the given style
+    pub fn draw_range_styled(
+        &self,
+        out: &mut dyn DrawSink,
+        step: f32,
+        style: RenderStyle,
+        range: Range<usize>,
+    ) {
+        let end = range.end.min(self.list.len());
+        let start = range.start.min(end);
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        for dir in self.segments_styled(style).take(start) {
+            dir.draw(&mut pen, step);
+        }
+        if start > 0 {
+            out.move_by(pen.x, pen.y);
+        }
+        for dir in self.segments_styled(style).skip(start).take(end - start) {
+            dir.draw(out, step);
+        }
+    }
+    /// Vertices of the curve with segments of length `step`, starting at `origin`.
+    ///
+    /// This includes the final point, so there's one more point than there are segments.
+    pub fn points(&self, step: f32, origin: (f32, f32)) -> Vec<(f32, f32)> {
+        self.iter_points(step, origin).collect()
+    }
+    /// Same as [`Self::points`], but yields the vertices one at a time instead of collecting them
+    pub fn iter_points(
+        &self,
+        step: f32,
+        origin: (f32, f32),
+    ) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let mut pen = Pen {
+            x: origin.0,
+            y: origin.1,
+        };
+        std::iter::once(origin).chain(self.list.iter().map(move |dir| {
+            dir.draw(&mut pen, step);
+            (pen.x, pen.y)
+        }))
+    }
+    /// Vertices of the curve drawn in a `size`-sized square, including the final point
+    fn vertices(&self, size: f32) -> Vec<(f32, f32)> {
+        self.points(self.step(size), (size * 0.25, size * 0.5))
+    }
+    /// Vertices of the curve rotated, scaled and translated so it starts at `a` and ends at `b`.
+    ///
+    /// If `a == b` the curve collapses onto that point. The endpoints are placed exactly, rather
+    /// than through the transform, so they never pick up rounding error.
+    pub fn fit_between(&self, a: (f32, f32), b: (f32, f32)) -> Vec<(f32, f32)> {
+        let mut points = self.vertices(1.0);
+        let (p0, p1) = (points[0], points[points.len() - 1]);
+        // map z to a + (z - p0) * (b - a) / (p1 - p0) as complex numbers
+        let (ux, uy) = (p1.0 - p0.0, p1.1 - p0.1);
+        let (vx, vy) = (b.0 - a.0, b.1 - a.1);
+        let norm = ux * ux + uy * uy;
+        let (re, im) = ((vx * ux + vy * uy) / norm, (vy * ux - vx * uy) / norm);
+        for p in &mut points {
+            let (dx, dy) = (p.0 - p0.0, p.1 - p0.1);
+            *p = (a.0 + dx * re - dy * im, a.1 + dx * im + dy * re);
+        }
+        let last = points.len() - 1;
+        points[0] = a;
+        points[last] = b;
+        points
+    }
+    /// Midpoints of consecutive segments in order, forming the curve's dual polyline
+    pub fn dual(&self, size: f32) -> Vec<(f32, f32)> {
+        self.vertices(size)
+            .windows(2)
+            .map(|w| ((w[0].0 + w[1].0) * 0.5, (w[0].1 + w[1].1) * 0.5))
+            .collect()
+    }
+    /// Smooth the curve by Chaikin corner-cutting `iterations` times.
+    ///
+    /// Each iteration replaces every segment with the points a quarter and three quarters along
+    /// it, while keeping the two endpoints fixed.
+    pub fn chaikin(&self, size: f32, iterations: usize) -> Vec<(f32, f32)> {
+        let mut points = self.vertices(size);
+        for _ in 0..iterations {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            let mut next = Vec::with_capacity(points.len() * 2);
+            next.push(first);
+            for w in points.windows(2) {
+                let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+                next.push((x0 * 0.75 + x1 * 0.25, y0 * 0.75 + y1 * 0.25));
+                next.push((x0 * 0.25 + x1 * 0.75, y0 * 0.25 + y1 * 0.75));
+            }
+            next.push(last);
+            points = next;
+        }
+        points
+    }
+    /// Central second moments of the vertices drawn in a `size`-sized square, as the mean of
+    /// `(dx * dx, dy * dy, dx * dy)` over offsets from the centroid.
+    ///
+    /// These are the entries of the covariance matrix, whose eigenvectors give the axes of the
+    /// curve's inertia ellipse.
+    pub fn second_moments(&self, size: f32) -> (f32, f32, f32) {
+        let points = self.vertices(size);
+        let n = points.len() as f32;
+        let (sx, sy) = points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+        let (cx, cy) = (sx / n, sy / n);
+        let (xx, yy, xy) = points
+            .iter()
+            .fold((0.0, 0.0, 0.0), |(xx, yy, xy), &(x, y)| {
+                let (dx, dy) = (x - cx, y - cy);
+                (xx + dx * dx, yy + dy * dy, xy + dx * dy)
+            });
+        (xx / n, yy / n, xy / n)
+    }
+    /// Root-mean-square distance of the vertices from their centroid, for the curve drawn in a
+    /// `size`-sized square
+    pub fn spread(&self, size: f32) -> f32 {
+        let (xx, yy, _) = self.second_moments(size);
+        (xx + yy).sqrt()
+    }
+    /// Signed area of the vertices drawn in a `size`-sized square, treated as a polygon closed
+    /// from the end back to the start.
+    ///
+    /// This is positive when the polygon winds counterclockwise in y-down screen coordinates.
+    pub fn signed_area(&self, size: f32) -> f32 {
+        let points = self.vertices(size);
+        let twice = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(&(x0, y0), &(x1, y1))| x1 * y0 - x0 * y1)
+            .sum::<f32>();
+        twice * 0.5
+    }
+    /// Estimate the box-counting dimension of the curve drawn in a `size`-sized square.
+    ///
+    /// The curve is rasterized onto grids aligned to the lattice its vertices lie on, with boxes
+    /// from twice the [grid spacing](Self::grid_spacing) up to a sixteenth of its extent, doubling
+    /// each time, and the dimension is the least-squares slope of `ln(count)` against `ln(1 / box)`.
+    /// Curves too shallow for two grids are treated as lines.
+    ///
+    /// Boxes along the boundary, which is itself a fractal, inflate the counts at coarser scales,
+    /// so the estimate approaches 2 slowly: a depth 14 dragon comes out around 1.83.
+    pub fn box_counting_dimension(&self, size: f32) -> f32 {
+        let points = self.vertices(size);
+        let step = self.step(size);
+        let spacing = self.grid_spacing(step, RenderStyle::Diagonal45);
+        let (min_x, min_y, max_x, max_y) = self.info(size).bounds;
+        // put the vertices in the middle of the boxes so none of them sit on an edge
+        let origin = (min_x - spacing * 0.5, min_y - spacing * 0.5);
+        let extent = (max_x - min_x).max(max_y - min_y);
+        let mut cell = spacing * 2.0;
+        let mut samples = Vec::new();
+        while cell <= extent / 16.0 {
+            let count = occupied_boxes(&points, origin, cell, step);
+            samples.push(((1.0 / cell as f64).ln(), (count as f64).ln()));
+            cell *= 2.0;
+        }
+        if samples.len() < 2 {
+            return 1.0;
+        }
+        let n = samples.len() as f64;
+        let (sx, sy) = samples
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+        let (sxx, sxy) = samples
+            .iter()
+            .fold((0.0, 0.0), |(sxx, sxy), &(x, y)| (sxx + x * x, sxy + x * y));
+        ((n * sxy - sx * sy) / (n * sxx - sx * sx)) as f32
+    }
+    /// Rough box-counting dimension of the curve with segments of length `step`, from just the
+    /// two grids with boxes `grid` and `2 * grid` across.
+    ///
+    /// This is the slope between those two counts rather than the fit over many sizes that
+    /// [`Self::box_counting_dimension`] does, so it's cheaper and lets the scale be picked, but the
+    /// result depends more on where the grid happens to fall.
+    pub fn box_count_dimension(&self, step: f32, grid: f32) -> f32 {
+        let points = self.points(step, (0.0, 0.0));
+        let (min_x, min_y, _, _) = self.bounds(step);
+        let fine = occupied_boxes(&points, (min_x, min_y), grid, step);
+        let coarse = occupied_boxes(&points, (min_x, min_y), grid * 2.0, step);
+        (fine as f32 / coarse as f32).log2()
+    }
+    /// Lattice cells visited by the vertices of the curve in order, starting from `(0, 0)` and
+    /// moving one cell per segment, with y increasing downwards.
+    ///
+    /// Only curves made entirely of horizontal and vertical segments walk a lattice, which for a
+    /// horizontal or vertical start means every even depth. Returns `None` for any other curve.
+    pub fn cell_walk(&self) -> Option<impl Iterator<Item = (i32, i32)> + '_> {
+        if !self.list.iter().all(|&d| d as u8 % 2 == 1) {
+            return None;
+        }
+        let walk = self
+            .list
+            .iter()
+            .map(Dir::offset)
+            .scan((0, 0), |pos, (dx, dy)| {
+                *pos = (pos.0 + dx, pos.1 + dy);
+                Some(*pos)
+            });
+        Some(std::iter::once((0, 0)).chain(walk))
+    }
+    /// Number of pairs of segments that cross or overlap each other, rather than just touching.
+    ///
+    /// Pairs are counted if they run along the same stretch, cross through each other's middles,
+    /// or pass through the same vertex from opposite sides of each other. The Heighway dragon only
+    /// ever touches itself, so this is 0 for it at every depth, while the Lévy C curve overlaps
+    /// from depth 4 on. The curve is walked on an integer lattice, so this doesn't depend on the
+    /// segment length and takes linear time.
+    pub fn self_intersections(&self) -> usize {
+        let mut pos = (0, 0);
+        let mut mids = HashMap::<(i32, i32), Vec<((i32, i32), (i32, i32))>>::new();
+        // directions out of each vertex the curve passes through, as index pairs
+        let mut visits = HashMap::<(i32, i32), Vec<(u8, u8)>>::new();
+        for (i, dir) in self.list.iter().enumerate() {
+            let (dx, dy) = dir.offset();
+            let next = (pos.0 + dx, pos.1 + dy);
+            mids.entry((pos.0 + next.0, pos.1 + next.1))
+                .or_default()
+                .push((pos.min(next), pos.max(next)));
+            if let Some(prev) = i.checked_sub(1).map(|i| self.list[i]) {
+                visits
+                    .entry(pos)
+                    .or_default()
+                    .push((prev.opposite() as u8, *dir as u8));
+            }
+            pos = next;
+        }
+        // segments of the same length with the same midpoint either overlap or cross in an X
+        let through = mids
+            .values()
+            .map(|segs| segs.len() * (segs.len() - 1) / 2)
+            .sum::<usize>();
+        let at_vertices = visits
+            .values()
+            .map(|v| {
+                let mut count = 0;
+                for (i, &(a0, a1)) in v.iter().enumerate() {
+                    for &(b0, b1) in &v[i + 1..] {
+                        // shared directions are overlaps, which were already counted
+                        if a0 == b0 || a0 == b1 || a1 == b0 || a1 == b1 {
+                            continue;
+                        }
+                        let between = |x: u8| (x + 8 - a0) % 8 < (a1 + 8 - a0) % 8;
+                        if between(b0) != between(b1) {
+                            count += 1;
+                        }
+                    }
+                }
+                count
+            })
+            .sum::<usize>();
+        through + at_vertices
+    }
+    /// Index of the first vertex of the [cell walk](Self::cell_walk) that lands on `cell`.
+    ///
+    /// This maps 2D lattice coordinates to a 1D, locality-preserving position along the curve.
+    /// Returns `None` if the cell is never visited or the curve doesn't walk a lattice.
+    pub fn index_of_cell(&self, cell: (i32, i32)) -> Option<usize> {
+        self.cell_walk()?.position(|c| c == cell)
+    }
+    /// Bounding box of the curve with segments of length `step`, relative to its starting point,
+    /// as `(min_x, min_y, max_x, max_y)`
+    pub fn bounds(&self, step: f32) -> (f32, f32, f32, f32) {
+        self.bounds_styled(step, RenderStyle::Diagonal45)
+    }
+    /// Same as [`Self::bounds`], for the curve drawn with the given style
+    pub fn bounds_styled(&self, step: f32, style: RenderStyle) -> (f32, f32, f32, f32) {
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        let mut bounds = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for dir in self.segments_styled(style) {
+            dir.draw(&mut pen, step);
+            bounds.0 = bounds.0.min(pen.x);
+            bounds.1 = bounds.1.min(pen.y);
+            bounds.2 = bounds.2.max(pen.x);
+            bounds.3 = bounds.3.max(pen.y);
+        }
+        bounds
+    }
+    /// Spacing of the square grid, aligned to the start of the curve, that every vertex lies on
+    /// when it's drawn with segments of length `step` in the given style.
+    ///
+    /// At any depth the segments are either all diagonal or all axis-aligned, so this is `step`
+    /// for axis-aligned ones and `step / √2` for diagonals. Curves spliced together from different
+    /// depths go by their first segment.
+    pub fn grid_spacing(&self, step: f32, style: RenderStyle) -> f32 {
+        self.segments_styled(style).next().map_or(step, |dir| {
+            let (x, y) = dir.as_vec();
+            step * x.abs().max(y.abs())
+        })
+    }
+    /// Largest distance from the starting point to any vertex, for segments of length `step`
+    pub fn radius(&self, step: f32) -> f32 {
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        let mut max = 0.0f32;
+        for dir in &self.list {
+            dir.draw(&mut pen, step);
+            max = max.max(pen.x.hypot(pen.y));
+        }
+        max
+    }
+    /// Trapezoids covering the inside of the curve, closed by a straight line from its end back
+    /// to its start, with segments of length `step` drawn in the given style from the origin.
+    ///
+    /// Each is `[top_left, top_right, bottom_right, bottom_left]`, with level tops and bottoms.
+    /// Slicing the outline into bands between the heights of its vertices handles its concave,
+    /// self-touching shape without a general triangulation, and `rule` decides which loops count
+    /// as inside the same way as [`Self::write_svg_filled`].
+    pub fn fill_trapezoids(
+        &self,
+        step: f32,
+        style: RenderStyle,
+        rule: FillRule,
+    ) -> Vec<[(f32, f32); 4]> {
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        let mut points = Vec::with_capacity(self.list.len() + 1);
+        points.push((0.0, 0.0));
+        for dir in self.segments_styled(style) {
+            dir.draw(&mut pen, step);
+            points.push((pen.x, pen.y));
+        }
+        let close = (points[points.len() - 1], points[0]);
+        let eps = step * 1e-3;
+        // every vertex starts or ends a band, and so does anywhere the closing line crosses the
+        // curve, so edges never cross in the middle of one
+        let mut ys = points.iter().map(|p| p.1).collect::<Vec<_>>();
+        let ((px, py), (qx, qy)) = close;
+        for w in points.windows(2) {
+            let ((ax, ay), (bx, by)) = (w[0], w[1]);
+            let denom = (qx - px) * (by - ay) - (qy - py) * (bx - ax);
+            if denom.abs() < f32::EPSILON {
+                continue;
+            }
+            let t = ((ax - px) * (by - ay) - (ay - py) * (bx - ax)) / denom;
+            let u = ((ax - px) * (qy - py) - (ay - py) * (qx - px)) / denom;
+            if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+                ys.push(py + (qy - py) * t);
+            }
+        }
+        ys.sort_by(f32::total_cmp);
+        ys.dedup_by(|a, b| *a - *b < eps);
+        // edges from top to bottom, with +1 winding going down; level ones don't cover anything
+        let mut edges = points
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .chain([close])
+            .filter_map(|(a, b)| match a.1.total_cmp(&b.1) {
+                Ordering::Less if b.1 - a.1 >= eps => Some((a, b, 1)),
+                Ordering::Greater if a.1 - b.1 >= eps => Some((b, a, -1)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by(|a, b| a.0.1.total_cmp(&b.0.1));
+        let x_at =
+            |(a, b): ((f32, f32), (f32, f32)), y: f32| a.0 + (b.0 - a.0) * (y - a.1) / (b.1 - a.1);
+        let inside = |winding: i32| match rule {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        };
+        let mut out = Vec::new();
+        let mut active = Vec::new();
+        let mut next = 0;
+        let mut crossings = Vec::new();
+        for band in ys.windows(2) {
+            let (y0, y1) = (band[0], band[1]);
+            while next < edges.len() && edges[next].0.1 <= y0 + eps {
+                active.push(edges[next]);
+                next += 1;
+            }
+            active.retain(|e| e.1.1 >= y1 - eps);
+            crossings.clear();
+            crossings.extend(
+                active
+                    .iter()
+                    .map(|&(a, b, w)| (x_at((a, b), y0), x_at((a, b), y1), w)),
+            );
+            crossings.sort_by(|a, b| (a.0 + a.1).total_cmp(&(b.0 + b.1)));
+            let mut winding = 0;
+            let mut left = None;
+            for &(x0, x1, w) in &crossings {
+                let was = inside(winding);
+                winding += w;
+                match (was, inside(winding)) {
+                    (false, true) => left = Some((x0, x1)),
+                    (true, false) => {
+                        if let Some((l0, l1)) = left.take() {
+                            out.push([(l0, y0), (x0, y0), (x1, y1), (l1, y1)]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        out
+    }
+    /// Write the curve drawn in a `size`-sized square as a Wavefront OBJ mesh, extruded into a
+    /// ribbon `thickness` wide and `height` tall.
+    ///
+    /// Each segment becomes a closed box with outward-facing quads, extended by half the thickness
+    /// at both ends so the boxes overlap at every turn. The y axis is flipped so the ribbon isn't
+    /// mirrored when viewed from above.
+    pub fn write_obj(
+        &self,
+        size: f32,
+        thickness: f32,
+        height: f32,
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        writeln!(w, "# dragon curve, depth {}", self.depth)?;
+        writeln!(w, "o dragon")?;
+        let half = thickness * 0.5;
+        let points = self.vertices(size);
+        for seg in points.windows(2) {
+            let (ax, ay) = (seg[0].0, -seg[0].1);
+            let (bx, by) = (seg[1].0, -seg[1].1);
+            let len = (bx - ax).hypot(by - ay);
+            let (dx, dy) = ((bx - ax) / len * half, (by - ay) / len * half);
+            let (nx, ny) = (-dy, dx);
+            let corners = [
+                (ax - dx - nx, ay - dy - ny),
+                (bx + dx - nx, by + dy - ny),
+                (bx + dx + nx, by + dy + ny),
+                (ax - dx + nx, ay - dy + ny),
+            ];
+            for z in [0.0, height] {
+                for (x, y) in corners {
+                    writeln!(w, "v {x} {y} {z}")?;
+                }
+            }
+        }
+        for i in 0..points.len() - 1 {
+            let b = i * 8 + 1;
+            for [p, q, r, s] in [
+                [0, 3, 2, 1],
+                [4, 5, 6, 7],
+                [0, 1, 5, 4],
+                [1, 2, 6, 5],
+                [2, 3, 7, 6],
+                [3, 0, 4, 7],
+            ] {
+                writeln!(w, "f {} {} {} {}", b + p, b + q, b + r, b + s)?;
+            }
+        }
+        Ok(())
+    }
+    /// Build a `lyon` path through the vertices of the curve drawn in a `size`-sized square
+    #[cfg(feature = "lyon")]
+    pub fn to_lyon_path(&self, size: f32) -> lyon::path::Path {
+        use lyon::math::point;
+        let points = self.vertices(size);
+        let mut builder = lyon::path::Path::builder();
+        builder.reserve(points.len(), points.len());
+        builder.begin(point(points[0].0, points[0].1));
+        for &(x, y) in &points[1..] {
+            builder.line_to(point(x, y));
+        }
+        builder.end(false);
+        builder.build()
+    }
+    /// Write the curve as an SVG document, with segments scaled as described in [`Self::step`].
+    ///
+    /// The path starts at the origin, and the `viewBox` fits the bounds of the curve, so the
+    /// document is only as big as the curve itself.
+    pub fn write_svg(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
+        self.write_svg_styled(size, SvgStyle::default(), w)
+    }
+    /// Same as [`Self::write_svg`], with coordinates rounded to `precision` decimal places
+    pub fn write_svg_with(&self, size: f32, precision: usize, w: &mut dyn Write) -> fmt::Result {
+        let style = SvgStyle {
+            precision: Some(precision),
+            ..SvgStyle::default()
+        };
+        self.write_svg_styled(size, style, w)
+    }
+    /// Same as [`Self::write_svg`], in the given style
+    pub fn write_svg_styled(&self, size: f32, style: SvgStyle, w: &mut dyn Write) -> fmt::Result {
+        self.write_svg_styled_inner(size, style, None, w)
+    }
+    /// Same as [`Self::write_svg_styled`], but with each segment stroked in the color returned by
+    /// `color`, as described in [`Self::write_svg_colored`]. Each run of one color is its own
+    /// `<path>`, so a dash pattern starts over at every change of color.
+    pub fn write_svg_styled_colored(
+        &self,
+        size: f32,
+        style: SvgStyle,
+        color: &mut dyn FnMut(usize, Dir, (f32, f32)) -> [u8; 3],
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        self.write_svg_styled_inner(size, style, Some(color), w)
+    }
+    fn write_svg_styled_inner(
+        &self,
+        size: f32,
+        style: SvgStyle,
+        color: Option<&mut SegmentColor>,
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        let step = self.step(size);
+        let pad = (style.stroke_width * 0.5).max(style.markers.unwrap_or(0.0));
+        self.write_svg_open(step, pad, style, w)?;
+        if style.rotation != 0.0 {
+            write!(
+                w,
+                r#"<g transform="rotate({})">"#,
+                Num(style.rotation, style.precision)
+            )?;
+        }
+        if style.grid {
+            // cover the whole view, which is bigger than the unturned curve once it's turned
+            let (min_x, min_y, max_x, max_y) =
+                self.bounds_rotated(step, style.render, style.rotation);
+            let corners = [
+                (min_x - pad, min_y - pad),
+                (max_x + pad, min_y - pad),
+                (min_x - pad, max_y + pad),
+                (max_x + pad, max_y + pad),
+            ];
+            write_svg_grid(
+                rotated_bounds(corners, -style.rotation),
+                0.0,
+                self.grid_spacing(step, style.render),
+                style.precision,
+                w,
+            )?;
+        }
+        let n = |v: f32| Num(v, style.precision);
+        let open_path = |stroke: &str, w: &mut dyn Write| {
+            write!(
+                w,
+                r#"<path style="stroke:{stroke};stroke-width:{}"#,
+                n(style.stroke_width)
+            )?;
+            if let Some([on, off]) = style.dash.pattern(style.stroke_width) {
+                write!(w, ";stroke-dasharray:{} {}", n(on), n(off))?;
+            }
+            w.write_str(r#";fill:none" d=""#)
+        };
+        if let Some(color) = color {
+            let mut pen = Pen { x: 0.0, y: 0.0 };
+            let mut run = None;
+            for (i, dir) in self.segments_styled(style.render).enumerate() {
+                let (x, y) = (pen.x, pen.y);
+                dir.draw(&mut pen, step);
+                let [r, g, b] = color(i, dir, ((x + pen.x) * 0.5, (y + pen.y) * 0.5));
+                if run != Some([r, g, b]) {
+                    if run.is_some() {
+                        w.write_str(r#""/>"#)?;
+                    }
+                    open_path(&format!("#{r:02x}{g:02x}{b:02x}"), w)?;
+                    write!(w, "M{} {}", n(x), n(y))?;
+                    run = Some([r, g, b]);
+                }
+                dir.draw(
+                    &mut SvgPath {
+                        writer: w,
+                        precision: style.precision,
+                    },
+                    step,
+                )?;
+            }
+            if run.is_some() {
+                w.write_str(r#""/>"#)?;
+            }
+        } else {
+            open_path("black", w)?;
+            self.write_path_data(step, (0.0, 0.0), style, false, w)?;
+            w.write_str(r#""/>"#)?;
+        }
+        if let Some(radius) = style.markers {
+            let n = |v: f32| Num(v, style.precision);
+            let mut pen = Pen { x: 0.0, y: 0.0 };
+            for dir in self.segments_styled(style.render) {
+                dir.draw(&mut pen, step);
+            }
+            write!(
+                w,
+                r##"<circle cx="0" cy="0" r="{r}" fill="#2a2"/><circle cx="{}" cy="{}" r="{r}" fill="#d22"/>"##,
+                n(pen.x),
+                n(pen.y),
+                r = n(radius),
+            )?;
+        }
+        if style.rotation != 0.0 {
+            w.write_str("</g>")?;
+        }
+        w.write_str("</svg>")
+    }
+    /// Same as [`Self::write_svg`], but with each segment stroked in the color returned by `color`.
+    ///
+    /// `color` gets the index and direction of each segment, along with its midpoint relative to
+    /// the start of the curve, and returns an RGB color. Runs of consecutive segments with the
+    /// same color share a single `<path>`.
+    pub fn write_svg_colored(
+        &self,
+        size: f32,
+        color: &mut dyn FnMut(usize, Dir, (f32, f32)) -> [u8; 3],
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        self.write_svg_styled_colored(size, SvgStyle::default(), color, w)
+    }
+    /// Same as [`Self::write_svg`], but with every corner rounded off into a quadratic curve with
+    /// the given radius, as described in [`Dir::draw_rounded`]
+    pub fn write_svg_rounded(&self, size: f32, radius: f32, w: &mut dyn Write) -> fmt::Result {
+        let step = self.step(size);
+        // the corners cut inside of the vertices, so the bounds still fit
+        self.write_svg_open(step, 0.5, SvgStyle::default(), w)?;
+        w.write_str(r#"<path style="stroke:black;stroke-width:1;fill:none" d="M0 0"#)?;
+        let mut path = SvgPath {
+            writer: w,
+            precision: Some(SVG_PRECISION),
+        };
+        for (i, &dir) in self.list.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|i| self.list[i]);
+            let next = self.list.get(i + 1).copied();
+            let (line, corner) = dir.draw_rounded(prev, next, &mut path, step, radius);
+            line?;
+            corner.transpose()?;
+        }
+        w.write_str(r#""/></svg>"#)
+    }
+    /// Companion curve that forms the twindragon together with this one.
+    ///
+    /// The twin is this curve turned 180° about the midpoint between its endpoints, so it starts
+    /// where this curve ends and ends where this one starts. For the Heighway dragon past depth
+    /// 0, the two share no segments, and translating the pair by the vector between the
+    /// endpoints and its 90° rotation covers every edge of the lattice exactly once. Lévy C
+    /// curves already overlap themselves, so they don't tile.
+    pub fn twin(&self) -> Self {
+        let mut twin = self.clone();
+        twin.rotate_by(4);
+        twin
+    }
+    /// Write an SVG document containing both the curve and its [twin](Self::twin), each as its own
+    /// path, with a `viewBox` fitting the pair
+    pub fn write_svg_twin(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
+        let step = self.step(size);
+        let twin = self.twin();
+        let style = SvgStyle::default();
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        for dir in &self.list {
+            dir.draw(&mut pen, step);
+        }
+        let end = (pen.x, pen.y);
+        let (min_x, min_y, max_x, max_y) = self.bounds(step);
+        // the twin is the curve turned around, so its bounds are flipped to the other endpoint
+        let bounds = (
+            min_x.min(end.0 - max_x),
+            min_y.min(end.1 - max_y),
+            max_x.max(end.0 - min_x),
+            max_y.max(end.1 - min_y),
+        );
+        write_svg_tag(bounds, 0.5, style, w)?;
+        for (curve, origin) in [(self, (0.0, 0.0)), (&twin, end)] {
+            w.write_str(r#"<path style="stroke:black;stroke-width:1;fill:none" d=""#)?;
+            curve.write_path_data(step, origin, style, false, w)?;
+            w.write_str(r#""/>"#)?;
+        }
+        w.write_str("</svg>")
+    }
+    /// Write the opening `<svg>` tag, sized and with a `viewBox` fitting the bounds of the curve
+    /// with segments of length `step`, plus `pad` on every side so the stroke isn't clipped
+    fn write_svg_open(
+        &self,
+        step: f32,
+        pad: f32,
+        style: SvgStyle,
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        let bounds = self.bounds_rotated(step, style.render, style.rotation);
+        write_svg_tag(bounds, pad, style, w)
+    }
+    /// Bounds of the curve drawn in `style`, as in [`Self::bounds_styled`], after turning it
+    /// clockwise around its start by `degrees`
+    fn bounds_rotated(&self, step: f32, style: RenderStyle, degrees: f32) -> (f32, f32, f32, f32) {
+        if degrees == 0.0 {
+            return self.bounds_styled(step, style);
+        }
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        let points = std::iter::once((0.0, 0.0)).chain(self.segments_styled(style).map(|dir| {
+            dir.draw(&mut pen, step);
+            (pen.x, pen.y)
+        }));
+        rotated_bounds(points, degrees)
+    }
+    /// Contents of the `d` attribute of the path [`Self::write_svg`] writes, for segments of length
+    /// `step`, to use in a document of your own.
+    ///
+    /// The path starts at the origin and ends by moving back there, so it can be placed with a
+    /// `transform` and read back with [`Self::from_svg_path`].
+    pub fn path_data(&self, step: f32) -> String {
+        let mut out = String::new();
+        self.write_path_data(step, (0.0, 0.0), SvgStyle::default(), false, &mut out)
+            .expect("writing to a string never fails");
+        out
+    }
+    /// Write the contents of the `d` attribute of a path tracing the curve with segments of length
+    /// `step`, starting at `origin`, with the precision and render style from `style`.
+    ///
+    /// If `close` is set, the path is closed back to the start, otherwise the pen just moves there.
+    fn write_path_data(
+        &self,
+        step: f32,
+        origin: (f32, f32),
+        style: SvgStyle,
+        close: bool,
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        let n = |v: f32| Num(v, style.precision);
+        let start = format!("{} {}", n(origin.0), n(origin.1));
+        write!(w, "M{start}")?;
+        let mut path = SvgPath {
+            writer: w,
+            precision: style.precision,
+        };
+        for p in self.segments_styled(style.render) {
+            p.draw(&mut path, step)?;
+        }
+        if close {
+            w.write_str(" Z")
+        } else {
+            write!(w, " M{start}")
+        }
+    }
+    /// Write the curve as an SVG document containing a single closed, filled path with no stroke,
+    /// framed the same way as [`Self::write_svg`].
+    ///
+    /// The curve touches itself, so `fill.rule` decides whether the loops it encloses are filled
+    /// solid or alternate between filled and empty.
+    pub fn write_svg_filled(&self, size: f32, fill: SvgFill, w: &mut dyn Write) -> fmt::Result {
+        let SvgFill {
+            color: [r, g, b],
+            opacity,
+            rule,
+        } = fill;
+        let step = self.step(size);
+        self.write_svg_open(step, 0.0, SvgStyle::default(), w)?;
+        write!(
+            w,
+            r#"<path style="stroke:none;fill:#{r:02x}{g:02x}{b:02x};fill-opacity:{opacity};fill-rule:{}" d=""#,
+            rule.as_str(),
+        )?;
+        self.write_path_data(step, (0.0, 0.0), SvgStyle::default(), true, w)?;
+        write!(w, r#""/></svg>"#)
+    }
+    /// Write an SVG document overlaying the curve at every depth from 0 to `max_depth`, each as
+    /// its own `<path>` styled by `layer`.
+    ///
+    /// Every depth shares the same endpoints, so the layers line up without any extra framing, and
+    /// the `viewBox` fits all of them together.
+    pub fn write_svg_layers(
+        start: Dir,
+        flags: CurveFlags,
+        max_depth: u8,
+        size: f32,
+        layer: &dyn Fn(u8) -> LayerStyle,
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        let style = SvgStyle::default();
+        let bounds = Self::depth_bounds(start, flags, max_depth, size, style.render);
+        write_svg_tag(bounds, style.stroke_width * 0.5, style, w)?;
+        let mut curve = Self::new(start, flags);
+        for depth in 0..=max_depth {
+            curve.set_depth(depth);
+            let LayerStyle {
+                color: [r, g, b],
+                opacity,
+            } = layer(depth);
+            write!(
+                w,
+                r#"<path style="stroke:#{r:02x}{g:02x}{b:02x};stroke-opacity:{opacity};stroke-width:1;fill:none" d=""#
+            )?;
+            curve.write_path_data(curve.step(size), (0.0, 0.0), style, false, w)?;
+            w.write_str(r#""/>"#)?;
+        }
+        w.write_str("</svg>")
+    }
+    /// Bounds of the curve drawn in a `size`-sized square in `style`, as in [`Self::bounds_styled`],
+    /// covering every depth from 0 to `max_depth`
+    fn depth_bounds(
+        start: Dir,
+        flags: CurveFlags,
+        max_depth: u8,
+        size: f32,
+        style: RenderStyle,
+    ) -> (f32, f32, f32, f32) {
+        let mut curve = Self::new(start, flags);
+        let mut bounds = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for depth in 0..=max_depth {
+            curve.set_depth(depth);
+            let b = curve.bounds_styled(curve.step(size), style);
+            bounds = (
+                bounds.0.min(b.0),
+                bounds.1.min(b.1),
+                bounds.2.max(b.2),
+                bounds.3.max(b.3),
+            );
+        }
+        bounds
+    }
+    /// Write the curve as an EPS document, with segments scaled as described in [`Self::step`] and
+    /// a bounding box fitting the curve
+    pub fn write_eps(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
+        let step = self.step(size);
+        let (min_x, min_y, max_x, max_y) = self.bounds(step);
+        let pad = 0.5;
+        let (width, height) = (max_x - min_x + pad * 2.0, max_y - min_y + pad * 2.0);
+        let n = |v: f32| Num(v, Some(SVG_PRECISION));
+        writeln!(w, "%!PS-Adobe-3.0 EPSF-3.0")?;
+        writeln!(
+            w,
+            "%%BoundingBox: 0 0 {} {}",
+            width.ceil() as u32,
+            height.ceil() as u32
+        )?;
+        writeln!(w, "%%HiResBoundingBox: 0 0 {} {}", n(width), n(height))?;
+        writeln!(w, "%%Title: dragon curve, depth {}", self.depth)?;
+        writeln!(w, "%%EndComments")?;
+        writeln!(w, "1 setlinewidth")?;
+        // y points up in PostScript, so the top of the curve is at the top of the box
+        writeln!(w, "newpath {} {} moveto", n(pad - min_x), n(pad + max_y))?;
+        let mut ps = PostScript {
+            writer: w,
+            precision: Some(SVG_PRECISION),
+        };
+        for dir in &self.list {
+            dir.draw(&mut ps, step)?;
+        }
+        writeln!(w, "stroke")?;
+        writeln!(w, "showpage")?;
+        writeln!(w, "%%EOF")
+    }
+    /// Write the curve as a self-contained `tikzpicture`, with segments scaled as described in
+    /// [`Self::step`] and one unit to a point, starting at the origin
+    pub fn write_tikz(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
+        let step = self.step(size);
+        writeln!(w, "\\begin{{tikzpicture}}[x=1pt, y=1pt]")?;
+        write!(w, "\\draw (0,0)")?;
+        let mut path = TikzPath {
+            writer: w,
+            x: 0.0,
+            y: 0.0,
+            precision: Some(SVG_PRECISION),
+        };
+        for dir in &self.list {
+            dir.draw(&mut path, step)?;
+        }
+        writeln!(w, ";")?;
+        writeln!(w, "\\end{{tikzpicture}}")
+    }
+    /// Write the curve as a GeoJSON `Feature` with a `LineString` through its vertices, with
+    /// segments of length `step` starting at `origin`.
+    ///
+    /// The y axis is flipped, since latitude increases northwards. The properties hold the depth
+    /// and the names of the flags that are set.
+    pub fn write_geojson(&self, step: f32, origin: (f32, f32), w: &mut dyn Write) -> fmt::Result {
+        let n = |v: f32| Num(v, None);
+        write!(
+            w,
+            r#"{{"type":"Feature","properties":{{"depth":{},"flags":["#,
+            self.depth
+        )?;
+        for (i, (name, _)) in self.flags.iter_names().enumerate() {
+            if i > 0 {
+                w.write_str(",")?;
+            }
+            write!(w, "\"{name}\"")?;
+        }
+        w.write_str(r#"]},"geometry":{"type":"LineString","coordinates":["#)?;
+        for (i, (x, y)) in self.iter_points(step, (0.0, 0.0)).enumerate() {
+            if i > 0 {
+                w.write_str(",")?;
+            }
+            write!(w, "[{},{}]", n(origin.0 + x), n(origin.1 - y))?;
+        }
+        w.write_str("]}}")
+    }
+    /// Write the vertices of the curve with segments of length `step`, starting at `origin`, as
+    /// CSV with an `x,y` pair on each line.
+    ///
+    /// There's no header row, so tools like gnuplot can read it as is. Coordinates are in the same
+    /// y-down orientation as [`Self::points`].
+    pub fn write_points_csv(
+        &self,
+        step: f32,
+        origin: (f32, f32),
+        w: &mut dyn Write,
+    ) -> fmt::Result {
+        let n = |v: f32| Num(v, None);
+        for (x, y) in self.iter_points(step, origin) {
+            writeln!(w, "{},{}", n(x), n(y))?;
+        }
+        Ok(())
+    }
+    /// Same as [`Self::write_svg`], but streams directly to an [`io::Write`]
+    pub fn write_svg_io(&self, size: f32, w: &mut dyn io::Write) -> io::Result<()> {
+        let mut adapter = IoAdapter::new(w);
+        let res = self.write_svg(size, &mut adapter);
+        adapter.finish(res)
+    }
+    /// Write the curve at every depth from 0 to `max_depth` into `dir` as `frame_00.svg`,
+    /// `frame_01.svg`, and so on, for stitching into an animation.
+    ///
+    /// Every depth shares the same endpoints, and every frame gets the same `viewBox`, fitting the
+    /// curve at all of the depths, so nothing moves between frames except the curve itself.
+    pub fn write_svg_frames(
+        start: Dir,
+        flags: CurveFlags,
+        max_depth: u8,
+        size: f32,
+        dir: &Path,
+    ) -> io::Result<()> {
+        let style = SvgStyle::default();
+        let bounds = Self::depth_bounds(start, flags, max_depth, size, style.render);
+        fs::create_dir_all(dir)?;
+        let mut curve = Self::new(start, flags);
+        for depth in 0..=max_depth {
+            curve.set_depth(depth);
+            let file = File::create(dir.join(format!("frame_{depth:02}.svg")))?;
+            let mut adapter = IoAdapter::new(io::BufWriter::new(file));
+            let res = (|| {
+                write_svg_tag(bounds, style.stroke_width * 0.5, style, &mut adapter)?;
+                adapter.write_str(r#"<path style="stroke:black;stroke-width:1;fill:none" d=""#)?;
+                curve.write_path_data(curve.step(size), (0.0, 0.0), style, false, &mut adapter)?;
+                adapter.write_str(r#""/></svg>"#)
+            })();
+            adapter.finish(res)?;
+            io::Write::flush(&mut adapter.inner)?;
+        }
+        Ok(())
+    }
+}
+/// Write an opening `<svg>` tag, sized and with a `viewBox` fitting `(min_x, min_y, max_x, max_y)`
+/// plus `pad` on every side
+fn write_svg_tag(
+    (min_x, min_y, max_x, max_y): (f32, f32, f32, f32),
+    pad: f32,
+    style: SvgStyle,
+    w: &mut dyn Write,
+) -> fmt::Result {
+    let n = |v: f32| Num(v, style.precision);
+    let units = style.units.suffix();
+    let (width, height) = (n(max_x - min_x + pad * 2.0), n(max_y - min_y + pad * 2.0));
+    let (x, y) = (n(min_x - pad), n(min_y - pad));
+    write!(
+        w,
+        r#"<svg width="{width}{units}" height="{height}{units}" viewBox="{x} {y} {width} {height}" xmlns="http://www.w3.org/2000/svg">"#,
+    )?;
+    if let Some([r, g, b]) = style.background {
+        write!(
+            w,
+            r##"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="#{r:02x}{g:02x}{b:02x}"/>"##
+        )?;
+    }
+    Ok(())
+}
+/// Bounding box of `points` after turning them clockwise around the origin by `degrees`, the same
+/// way as an SVG `rotate`
+fn rotated_bounds(
+    points: impl IntoIterator<Item = (f32, f32)>,
+    degrees: f32,
+) -> (f32, f32, f32, f32) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let mut bounds = (
+        f32::INFINITY,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NEG_INFINITY,
+    );
+    for (x, y) in points {
+        let (x, y) = (x * cos - y * sin, x * sin + y * cos);
+        bounds.0 = bounds.0.min(x);
+        bounds.1 = bounds.1.min(y);
+        bounds.2 = bounds.2.max(x);
+        bounds.3 = bounds.3.max(y);
+    }
+    bounds
+}
+/// Write gridlines `spacing` apart, covering `(min_x, min_y, max_x, max_y)` plus `pad` on every
+/// side, with darker axes through the origin
+fn write_svg_grid(
+    (min_x, min_y, max_x, max_y): (f32, f32, f32, f32),
+    pad: f32,
+    spacing: f32,
+    precision: Option<usize>,
+    w: &mut dyn Write,
+) -> fmt::Result {
+    let n = |v: f32| Num(v, precision);
+    let (x0, y0, x1, y1) = (min_x - pad, min_y - pad, max_x + pad, max_y + pad);
+    w.write_str(r#"<path style="stroke:#ddd;stroke-width:0.5;fill:none" d=""#)?;
+    for i in (x0 / spacing).ceil() as i32..=(x1 / spacing).floor() as i32 {
+        if i != 0 {
+            write!(w, "M{} {}V{}", n(i as f32 * spacing), n(y0), n(y1))?;
+        }
+    }
+    for j in (y0 / spacing).ceil() as i32..=(y1 / spacing).floor() as i32 {
+        if j != 0 {
+            write!(w, "M{} {}H{}", n(x0), n(j as f32 * spacing), n(x1))?;
+        }
+    }
+    write!(
+        w,
+        r#""/><path style="stroke:#999;stroke-width:0.5;fill:none" d="M{} 0H{}M0 {}V{}"/>"#,
+        n(x0),
+        n(x1),
+        n(y0),
+        n(y1),
+    )
+}
+/// Number of `cell`-sized boxes, on a grid aligned to `origin`, touched by the polyline through
+/// `points`, whose segments are at most `step` long
+fn occupied_boxes(points: &[(f32, f32)], origin: (f32, f32), cell: f32, step: f32) -> usize {
+    let samples = (step * 4.0 / cell).ceil().max(1.0) as usize;
+    let mut boxes = HashSet::new();
+    let mut mark = |(x, y): (f32, f32)| {
+        let i = ((x - origin.0) / cell).floor() as i32;
+        let j = ((y - origin.1) / cell).floor() as i32;
+        boxes.insert((i, j));
+    };
+    mark(points[0]);
+    for w in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        for k in 1..=samples {
+            let t = k as f32 / samples as f32;
+            mark((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+        }
+    }
+    boxes.len()
+}
+
+/// Writes the curve as an L-system string, prefixed with its starting direction from
+/// [`DragonCurve::start`].
+///
+/// Every segment is an `F`, and between them `+` turns left and `-` turns right by 90°, the same
+/// way as [`Dir::left`] and [`Dir::right`]. A depth 2 dragon started along `+x` is `→: F-F-F+F`.
+/// Generated and appended curves only ever turn by right angles, but a turn by 45° is written as
+/// `<` to the left or `>` to the right after any 90° turns rather than being lost.
+impl fmt::Display for DragonCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.list.is_empty() {
+            return Ok(());
+        }
+        write!(f, "{}: F", self.start())?;
+        for turn in self.turns() {
+            // 45° steps to the right, taken between -3 and 4 so they go the short way around
+            let steps = if turn > 4 { turn as i8 - 8 } else { turn as i8 };
+            let (quarter, half) = if steps < 0 { ("+", "<") } else { ("-", ">") };
+            for _ in 0..steps.unsigned_abs() / 2 {
+                f.write_str(quarter)?;
+            }
+            if steps % 2 != 0 {
+                f.write_str(half)?;
+            }
+            f.write_str("F")?;
+        }
+        Ok(())
+    }
+}
+
+/// Curves are equal if they have the same depth and flags and every segment matches, so ones that
+/// were edited, or started in different directions, only compare equal if they draw the same.
+impl PartialEq for DragonCurve {
+    fn eq(&self, other: &Self) -> bool {
+        self.depth == other.depth && self.flags == other.flags && self.list == other.list
+    }
+}
+impl Eq for DragonCurve {}
+/// Hashes the same fields that equality compares, so equal curves always hash the same
+impl Hash for DragonCurve {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.depth.hash(state);
+        self.flags.hash(state);
+        self.list.hash(state);
+    }
+}
+/// The depth 0 dragon, pointing along `+x`
+impl Default for DragonCurve {
+    fn default() -> Self {
+        Self::new(Dir::default(), CurveFlags::default())
+    }
+}
+
+/// Builder for a [`DragonCurve`] at a given depth, from [`DragonCurve::builder`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DragonCurveBuilder {
+    start: Dir,
+    flags: CurveFlags,
+    depth: u8,
+}
+impl DragonCurveBuilder {
+    /// Direction of the only segment at depth 0
+    pub fn start(mut self, start: Dir) -> Self {
+        self.start = start;
+        self
+    }
+    pub fn flags(mut self, flags: CurveFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+    pub fn depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+    /// Build the curve, the same as [`DragonCurve::new`] followed by [`DragonCurve::set_depth`]
+    pub fn build(self) -> DragonCurve {
+        let mut curve = DragonCurve::new(self.start, self.flags);
+        curve.set_depth(self.depth);
+        curve
+    }
+}
+
+/// Serialized form of a [`DragonCurve`], from which the rest of it can be rebuilt
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CurveParams {
+    start: Dir,
+    depth: u8,
+    flags: CurveFlags,
+}
+#[cfg(feature = "serde")]
+impl From<DragonCurve> for CurveParams {
+    fn from(curve: DragonCurve) -> Self {
+        Self {
+            start: curve.start(),
+            depth: curve.depth,
+            flags: curve.flags,
+        }
+    }
+}
+/// Most segments a deserialized curve is allowed to have, so that untrusted input can't ask for
+/// an arbitrarily deep one
+#[cfg(feature = "serde")]
+pub const DESERIALIZE_BUDGET: usize = 1 << 24;
+
+/// Error from deserializing a [`DragonCurve`] whose parameters can't be rebuilt
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidParams {
+    /// The depth has more segments than [`DESERIALIZE_BUDGET`]
+    TooManySegments(TooManySegments),
+    /// The curve is [`CurveFlags::CUSTOM`], whose segments don't follow from the parameters
+    Custom,
+}
+#[cfg(feature = "serde")]
+impl fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooManySegments(err) => err.fmt(f),
+            Self::Custom => f.write_str("custom curves can't be rebuilt from their parameters"),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl std::error::Error for InvalidParams {}
+#[cfg(feature = "serde")]
+impl TryFrom<CurveParams> for DragonCurve {
+    type Error = InvalidParams;
+
+    fn try_from(params: CurveParams) -> Result<Self, InvalidParams> {
+        if params.flags.contains(CurveFlags::CUSTOM) {
+            return Err(InvalidParams::Custom);
+        }
+        let mut curve = Self::new(params.start, params.flags);
+        curve
+            .try_set_depth(params.depth, DESERIALIZE_BUDGET)
+            .map_err(InvalidParams::TooManySegments)?;
+        Ok(curve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Dir::*;
+
+    /// Every combination of the flags that affect generation
+    const ALL_FLAGS: [CurveFlags; 4] = [
+        CurveFlags::DRAGON,
+        CurveFlags::FLIP,
+        CurveFlags::LEVY,
+        CurveFlags::LEVY.union(CurveFlags::FLIP),
+    ];
+
+    fn build(flags: CurveFlags, depth: u8) -> DragonCurve {
+        DragonCurve::builder().flags(flags).depth(depth).build()
+    }
+
+    /// Check the curve with `flags` at every depth from 0 against `expected`, indexed by depth
+    fn check_depths(flags: CurveFlags, expected: &[&[Dir]]) {
+        for (depth, &segments) in expected.iter().enumerate() {
+            let curve = build(flags, depth as u8);
+            assert_eq!(curve.list(), segments, "{flags:?} at depth {depth}");
+        }
+    }
+
+    #[test]
+    fn opposite_directions() {
+        for dir in Dir::ALL {
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_ne!(dir.opposite(), dir);
+            let ((x, y), (ox, oy)) = (dir.as_vec(), dir.opposite().as_vec());
+            assert_eq!((x, y), (-ox, -oy), "{dir:?}");
+        }
+    }
+
+    #[test]
+    fn dragon_depths() {
+        check_depths(
+            CurveFlags::DRAGON,
+            &[
+                &[Np0],
+                &[Npp, Npm],
+                &[N0p, Np0, N0m, Np0],
+                &[Nmp, Npp, Npm, Npp, Npm, Nmm, Npm, Npp],
+                &[
+                    Nm0, N0p, Np0, N0p, Np0, N0m, Np0, N0p, Np0, N0m, Nm0, N0m, Np0, N0m, Np0, N0p,
+                ],
+            ],
+        );
+    }
+
+    #[test]
+    fn flipped_dragon_depths() {
+        check_depths(
+            CurveFlags::FLIP,
+            &[
+                &[Np0],
+                &[Npm, Npp],
+                &[N0m, Np0, N0p, Np0],
+                &[Nmm, Npm, Npp, Npm, Npp, Nmp, Npp, Npm],
+                &[
+                    Nm0, N0m, Np0, N0m, Np0, N0p, Np0, N0m, Np0, N0p, Nm0, N0p, Np0, N0p, Np0, N0m,
+                ],
+            ],
+        );
+    }
+
+    #[test]
+    fn levy_depths() {
+        check_depths(
+            CurveFlags::LEVY,
+            &[
+                &[Np0],
+                &[Npp, Npm],
+                &[N0p, Np0, Np0, N0m],
+                &[Nmp, Npp, Npp, Npm, Npp, Npm, Npm, Nmm],
+            ],
+        );
+    }
+
+    #[test]
+    fn flipped_levy_depths() {
+        check_depths(
+            CurveFlags::LEVY | CurveFlags::FLIP,
+            &[
+                &[Np0],
+                &[Npm, Npp],
+                &[N0m, Np0, Np0, N0p],
+                &[Nmm, Npm, Npm, Npp, Npm, Npp, Npp, Nmp],
+            ],
+        );
+    }
+
+    /// Append the segments of the curve from `p` to `q` at `depth`, by literally splitting the chord
+    /// at the corner of a right isosceles triangle standing on it, as in the iterated function
+    /// systems that define the curves.
+    ///
+    /// Both halves of a Lévy C curve are smaller copies running the same way. The second half of
+    /// a dragon is a copy running from `q` back to the corner, which is traced backwards here.
+    /// Unflipped curves put the corner on the right of the chord, which is `+y` from `+x`.
+    fn reference(p: (f64, f64), q: (f64, f64), depth: u8, flags: CurveFlags, out: &mut Vec<Dir>) {
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        if depth == 0 {
+            let len = dx.hypot(dy);
+            let nearest = Dir::ALL.into_iter().min_by(|a, b| {
+                let dist = |d: &Dir| {
+                    let (x, y) = d.as_vec();
+                    (x as f64 - dx / len).hypot(y as f64 - dy / len)
+                };
+                dist(a).total_cmp(&dist(b))
+            });
+            out.push(nearest.unwrap());
+            return;
+        }
+        let corner = if flags.contains(CurveFlags::FLIP) {
+            (p.0 + (dx + dy) * 0.5, p.1 + (dy - dx) * 0.5)
+        } else {
+            (p.0 + (dx - dy) * 0.5, p.1 + (dy + dx) * 0.5)
+        };
+        reference(p, corner, depth - 1, flags, out);
+        if flags.contains(CurveFlags::LEVY) {
+            reference(corner, q, depth - 1, flags, out);
+        } else {
+            let mut back = Vec::new();
+            reference(q, corner, depth - 1, flags, &mut back);
+            out.extend(back.iter().rev().map(Dir::opposite));
+        }
+    }
+
+    #[test]
+    fn matches_reference() {
+        for flags in ALL_FLAGS {
+            for start in Dir::ALL {
+                let (x, y) = start.as_vec();
+                for depth in 0..=10 {
+                    let mut expected = Vec::new();
+                    reference(
+                        (0.0, 0.0),
+                        (x as f64, y as f64),
+                        depth,
+                        flags,
+                        &mut expected,
+                    );
+                    let curve = DragonCurve::builder()
+                        .start(start)
+                        .flags(flags)
+                        .depth(depth)
+                        .build();
+                    assert_eq!(
+                        curve.list(),
+                        expected,
+                        "{flags:?} from {start:?} at depth {depth}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Number of drawing commands in the `d` attributes of every path in an SVG document
+    fn svg_segments(svg: &str) -> usize {
+        svg.split(r#" d=""#)
+            .skip(1)
+            .map(|rest| {
+                let d = &rest[..rest.find('"').unwrap()];
+                d.chars().filter(|c| matches!(c, 'l' | 'h' | 'v')).count()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn exporters_agree_on_segments() {
+        for flags in ALL_FLAGS {
+            for depth in 0..=6 {
+                let curve = build(flags, depth);
+                let step = curve.step(100.0);
+                let mut out = String::new();
+                let mut counts = Vec::new();
+                let mut count = |name, write: &dyn Fn(&mut String), segments: fn(&str) -> usize| {
+                    out.clear();
+                    write(&mut out);
+                    counts.push((name, segments(&out)));
+                };
+                count("svg", &|w| curve.write_svg(100.0, w).unwrap(), svg_segments);
+                count(
+                    "colored svg",
+                    &|w| {
+                        curve
+                            .write_svg_colored(100.0, &mut |i, _, _| [i as u8 % 2 * 255; 3], w)
+                            .unwrap()
+                    },
+                    svg_segments,
+                );
+                count("eps", &|w| curve.write_eps(100.0, w).unwrap(), |eps| {
+                    eps.lines().filter(|l| l.ends_with(" rlineto")).count()
+                });
+                count("tikz", &|w| curve.write_tikz(100.0, w).unwrap(), |tikz| {
+                    tikz.matches("-- (").count()
+                });
+                count(
+                    "geojson",
+                    &|w| curve.write_geojson(step, (0.0, 0.0), w).unwrap(),
+                    |json| {
+                        let coords = json.split(r#""coordinates":["#).nth(1).unwrap();
+                        coords.matches("],[").count()
+                    },
+                );
+                count(
+                    "csv",
+                    &|w| curve.write_points_csv(step, (0.0, 0.0), w).unwrap(),
+                    |csv| csv.lines().count() - 1,
+                );
+                count(
+                    "obj",
+                    &|w| curve.write_obj(100.0, 1.0, 1.0, w).unwrap(),
+                    |obj| obj.lines().filter(|l| l.starts_with("f ")).count() / 6,
+                );
+                for (name, segments) in counts {
+                    assert_eq!(
+                        segments,
+                        curve.len(),
+                        "{name} of {flags:?} at depth {depth}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_by_keeps_shape() {
+        for flags in ALL_FLAGS {
+            let curve = build(flags, 6);
+            let turns = curve.turns().collect::<Vec<_>>();
+            for by in 0..8 {
+                let mut rotated = curve.clone();
+                rotated.rotate_by(by);
+                assert!(
+                    rotated.turns().eq(turns.iter().copied()),
+                    "{flags:?} by {by}"
+                );
+                assert_eq!(rotated.total_turning(), curve.total_turning());
+                assert!(
+                    rotated
+                        .segments()
+                        .zip(curve.segments())
+                        .all(|(r, c)| r == c.rotate(by))
+                );
+            }
+            let mut full = curve.clone();
+            full.rotate_by(8);
+            assert_eq!(full, curve);
+        }
+    }
+
+    #[test]
+    fn box_counting_dimension_near_2() {
+        let dim = build(CurveFlags::DRAGON, 14).box_counting_dimension(1000.0);
+        assert!((dim - 2.0).abs() < 0.2, "estimated {dim}");
+        assert_eq!(
+            build(CurveFlags::DRAGON, 4).box_counting_dimension(1000.0),
+            1.0
+        );
+    }
+
+    #[test]
+    fn info_matches_queries() {
+        for flags in ALL_FLAGS {
+            for depth in [0, 1, 5, 8] {
+                let curve = build(flags, depth);
+                let size = 100.0;
+                let step = curve.step(size);
+                let info = curve.info(size);
+                assert_eq!(info.segments, curve.len());
+                assert_eq!(info.vertices, curve.vertex_count());
+                assert_eq!(info.length, curve.len() as f32 * step);
+                assert_eq!(info.turns, curve.turns().filter(|&t| t != 0).count());
+                for dir in Dir::ALL {
+                    let count = curve.segments().filter(|&d| d == dir).count();
+                    assert_eq!(info.histogram[dir as usize], count);
+                }
+                let points = curve.points(step, (size * 0.25, size * 0.5));
+                let n = points.len() as f32;
+                let (cx, cy) = info.centroid;
+                assert!((cx - points.iter().map(|p| p.0).sum::<f32>() / n).abs() < 1e-3);
+                assert!((cy - points.iter().map(|p| p.1).sum::<f32>() / n).abs() < 1e-3);
+                let (min_x, min_y, max_x, max_y) = curve.bounds(step);
+                let offset = (size * 0.25, size * 0.5);
+                let expected = (
+                    min_x + offset.0,
+                    min_y + offset.1,
+                    max_x + offset.0,
+                    max_y + offset.1,
+                );
+                assert_eq!(info.bounds, expected, "{flags:?} at depth {depth}");
+            }
+        }
+        assert!(
+            build(CurveFlags::DRAGON, 0)
+                .info(100.0)
+                .aspect_ratio
+                .is_nan()
+        );
+        assert_eq!(build(CurveFlags::DRAGON, 2).info(100.0).aspect_ratio, 2.0);
+    }
+
+    #[test]
+    fn rotate_to_points_first_segment() {
+        for flags in ALL_FLAGS {
+            for dir in Dir::ALL {
+                let mut curve = build(flags, 0);
+                curve.rotate_to(dir);
+                assert_eq!(curve.start(), dir, "{flags:?} at depth 0");
+                for depth in [3, 10] {
+                    let mut curve = build(flags, depth);
+                    curve.rotate_to(dir);
+                    assert_eq!(curve.segment_at(0), Some(dir), "{flags:?} at depth {depth}");
+                    // the first segment is the start turned once per level, so it takes set_start
+                    // to put the start itself in place
+                    curve.set_start(dir);
+                    assert_eq!(curve.start(), dir, "{flags:?} at depth {depth}");
+                    let fresh = DragonCurve::builder()
+                        .start(dir)
+                        .flags(flags)
+                        .depth(depth)
+                        .build();
+                    assert_eq!(curve, fresh);
+                }
+            }
+        }
+    }
+
+    /// Whether two points are within rounding of each other
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn dual_midpoints() {
+        for depth in [0, 1, 6] {
+            let curve = build(CurveFlags::DRAGON, depth);
+            assert_eq!(curve.dual(100.0).len(), curve.len());
+        }
+        // a depth 1 dragon goes from (25, 50) down to (50, 75) and back up to (75, 50)
+        let dual = build(CurveFlags::DRAGON, 1).dual(100.0);
+        assert!(close(dual[0], (37.5, 62.5)), "{dual:?}");
+        assert!(close(dual[1], (62.5, 62.5)), "{dual:?}");
+    }
+
+    #[test]
+    fn chaikin_cuts_corners() {
+        for flags in ALL_FLAGS {
+            let curve = build(flags, 5);
+            let mut segments = curve.len();
+            for iterations in 0..4 {
+                let points = curve.chaikin(100.0, iterations);
+                assert_eq!(points.len(), segments + 1, "{flags:?} after {iterations}");
+                assert_eq!(points[0], (25.0, 50.0));
+                assert!(close(points[points.len() - 1], (75.0, 50.0)));
+                segments = segments * 2 + 1;
+            }
+        }
+        let points = build(CurveFlags::DRAGON, 1).chaikin(100.0, 1);
+        assert!(close(points[2], (43.75, 68.75)), "{points:?}");
+    }
+
+    #[test]
+    fn num_trims_zeros() {
+        assert_eq!(Num(1.500, Some(3)).to_string(), "1.5");
+        assert_eq!(Num(2.0, Some(3)).to_string(), "2");
+        assert_eq!(Num(-0.0001, Some(3)).to_string(), "0");
+        assert_eq!(Num(100.0, Some(0)).to_string(), "100");
+        assert_eq!(Num(0.1234, None).to_string(), "0.1234");
+    }
+
+    #[test]
+    fn svg_units() {
+        let curve = build(CurveFlags::DRAGON, 0);
+        for (units, suffix) in [
+            (SvgUnits::Px, ""),
+            (SvgUnits::Mm, "mm"),
+            (SvgUnits::Pt, "pt"),
+        ] {
+            let style = SvgStyle {
+                units,
+                ..SvgStyle::default()
+            };
+            let mut svg = String::new();
+            curve.write_svg_styled(100.0, style, &mut svg).unwrap();
+            let header =
+                format!(r#"<svg width="51{suffix}" height="1{suffix}" viewBox="-0.5 -0.5 51 1" "#);
+            assert!(svg.starts_with(&header), "{svg}");
+        }
+    }
+
+    #[test]
+    fn signed_area_flips() {
+        // down, right, up and right again encloses one 25 by 25 square counterclockwise
+        assert_eq!(build(CurveFlags::DRAGON, 2).signed_area(100.0), 625.0);
+        for flags in [CurveFlags::DRAGON, CurveFlags::LEVY] {
+            for depth in [2, 5, 8] {
+                let area = build(flags, depth).signed_area(100.0);
+                let flipped = build(flags | CurveFlags::FLIP, depth).signed_area(100.0);
+                assert!(area != 0.0);
+                assert!((area + flipped).abs() < 1e-2, "{flags:?} at depth {depth}");
+            }
+        }
+    }
+
+    #[test]
+    fn spread_of_depth_1() {
+        // the vertices (25, 50), (50, 75), (75, 50) have their centroid at (50, 175 / 3)
+        let curve = build(CurveFlags::DRAGON, 1);
+        let (xx, yy, xy) = curve.second_moments(100.0);
+        assert!((xx - 1250.0 / 3.0).abs() < 1e-2, "{xx}");
+        assert!((yy - 1250.0 / 9.0).abs() < 1e-2, "{yy}");
+        assert!(xy.abs() < 1e-2, "{xy}");
+        assert!((curve.spread(100.0) - 5000f32.sqrt() / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn svg_layers_fit_every_depth() {
+        let mut svg = String::new();
+        DragonCurve::write_svg_layers(
+            Np0,
+            CurveFlags::DRAGON,
+            3,
+            100.0,
+            &|depth| LayerStyle::fade(depth, 3),
+            &mut svg,
+        )
+        .unwrap();
+        assert_eq!(svg.matches("<path ").count(), 4);
+        // depth 1 dips 25 below the chord, and depth 3 reaches 12.5 behind and above its start
+        assert!(
+            svg.starts_with(r#"<svg width="63.5" height="38.5" viewBox="-13 -13 63.5 38.5" "#),
+            "{svg}"
+        );
+        // the last layer is the same path as the single curve
+        let curve = build(CurveFlags::DRAGON, 3);
+        assert!(svg.contains(&curve.path_data(curve.step(100.0))));
+    }
+
+    #[test]
+    fn framing_keeps_endpoints() {
+        for flags in ALL_FLAGS {
+            for depth in [0, 1, 8] {
+                let curve = build(flags, depth);
+                let points = curve.points(curve.step(100.0), (25.0, 50.0));
+                assert_eq!(points[0], (25.0, 50.0));
+                let end = points[points.len() - 1];
+                assert!(
+                    close(end, (75.0, 50.0)),
+                    "{flags:?} at depth {depth} ends at {end:?}"
+                );
+            }
+        }
+        let mut svg = String::new();
+        build(CurveFlags::DRAGON, 0)
+            .write_svg(100.0, &mut svg)
+            .unwrap();
+        assert!(svg.contains(r#" d="M0 0 h50 M0 0""#), "{svg}");
+    }
+
+    #[cfg(feature = "lyon")]
+    #[test]
+    fn lyon_path_events() {
+        use lyon::path::Event;
+        for depth in [0, 1, 6] {
+            let curve = build(CurveFlags::DRAGON, depth);
+            let path = curve.to_lyon_path(100.0);
+            let events = path.iter().collect::<Vec<_>>();
+            // one begin and one end around a line per segment
+            assert_eq!(events.len(), curve.len() + 2);
+            let lines = events
+                .iter()
+                .filter(|e| matches!(e, Event::Line { .. }))
+                .count();
+            assert_eq!(lines, curve.len());
+            let aabb = lyon::algorithms::aabb::bounding_box(path.iter());
+            let (min_x, min_y, max_x, max_y) = curve.info(100.0).bounds;
+            assert!(close((aabb.min.x, aabb.min.y), (min_x, min_y)));
+            assert!(close((aabb.max.x, aabb.max.y), (max_x, max_y)));
+        }
+    }
+
+    #[test]
+    fn obj_ribbon_counts() {
+        let mut obj = String::new();
+        build(CurveFlags::DRAGON, 2)
+            .write_obj(100.0, 2.0, 5.0, &mut obj)
+            .unwrap();
+        // a box of 8 corners and 6 faces for each of the 4 segments
+        let vertices = obj.lines().filter(|l| l.starts_with("v ")).count();
+        assert_eq!(vertices, 32);
+        let faces = obj
+            .lines()
+            .filter_map(|l| l.strip_prefix("f "))
+            .collect::<Vec<_>>();
+        assert_eq!(faces.len(), 24);
+        for face in faces {
+            let indices = face
+                .split(' ')
+                .map(|i| i.parse::<usize>().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(indices.len(), 4);
+            assert!(indices.iter().all(|i| (1..=vertices).contains(i)), "{face}");
+        }
+    }
+
+    #[test]
+    fn index_of_cell_inverts_walk() {
+        for flags in ALL_FLAGS {
+            let curve = build(flags, 8);
+            let walk = curve.cell_walk().unwrap().collect::<Vec<_>>();
+            for (i, &cell) in walk.iter().enumerate() {
+                let first = curve.index_of_cell(cell).unwrap();
+                // cells visited more than once map back to their first visit
+                assert!(first <= i);
+                assert_eq!(walk[first], cell);
+                assert!(!walk[..first].contains(&cell));
+            }
+            assert_eq!(curve.index_of_cell((1000, 1000)), None);
+        }
+        assert_eq!(build(CurveFlags::DRAGON, 7).index_of_cell((0, 0)), None);
+    }
+
+    #[test]
+    fn svg_fill_attributes() {
+        let curve = build(CurveFlags::DRAGON, 6);
+        let mut outputs = Vec::new();
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let fill = SvgFill {
+                color: [0x12, 0xab, 0xff],
+                opacity: 0.5,
+                rule,
+            };
+            let mut svg = String::new();
+            curve.write_svg_filled(100.0, fill, &mut svg).unwrap();
+            let style = format!(
+                "stroke:none;fill:#12abff;fill-opacity:0.5;fill-rule:{}",
+                rule.as_str()
+            );
+            assert!(svg.contains(&style), "{svg}");
+            assert!(svg.ends_with(r#" Z"/></svg>"#));
+            outputs.push(svg);
+        }
+        assert_ne!(outputs[0], outputs[1]);
+        assert_eq!(
+            outputs[0].replace("nonzero", "evenodd"),
+            outputs[1],
+            "only the rule should differ"
+        );
+    }
+
+    #[test]
+    fn fit_between_lands_on_endpoints() {
+        let (a, b) = ((3.0, -2.0), (-7.5, 11.0));
+        for flags in ALL_FLAGS {
+            for depth in [0, 1, 7] {
+                let points = build(flags, depth).fit_between(a, b);
+                assert_eq!(points[0], a);
+                assert_eq!(points[points.len() - 1], b);
+                assert_eq!(points.len(), (1 << depth) + 1);
+            }
+        }
+        let collapsed = build(CurveFlags::DRAGON, 4).fit_between(a, a);
+        assert!(collapsed.iter().all(|&p| p == a), "{collapsed:?}");
+    }
+
+    #[test]
+    fn step_at_shallow_depths() {
+        for flags in [CurveFlags::DRAGON, CurveFlags::LEVY] {
+            let steps = (0..4)
+                .map(|depth| build(flags, depth).step(100.0))
+                .collect::<Vec<_>>();
+            // the chord is half of the square, and every level shrinks segments by √2
+            assert_eq!(steps[0], 50.0);
+            assert!(
+                (steps[1] - 50.0 * SCALE).abs() < 1e-4,
+                "{flags:?}: {steps:?}"
+            );
+            assert_eq!(steps[2], 25.0);
+            assert!(
+                (steps[3] - 25.0 * SCALE).abs() < 1e-4,
+                "{flags:?}: {steps:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reverse_retraces_points() {
+        for flags in ALL_FLAGS {
+            for depth in [0, 3, 8] {
+                let curve = build(flags, depth);
+                let step = curve.step(100.0);
+                let points = curve.points(step, (25.0, 50.0));
+                let mut reversed = curve.clone();
+                reversed.reverse();
+                let back = reversed.points(step, points[points.len() - 1]);
+                assert_eq!(back.len(), points.len());
+                assert!(
+                    back.iter()
+                        .zip(points.iter().rev())
+                        .all(|(&a, &b)| close(a, b)),
+                    "{flags:?} at depth {depth}"
+                );
+                reversed.reverse();
+                assert_eq!(reversed, curve);
+            }
+        }
+    }
+
+    #[test]
+    fn styled_colored_svg_keeps_style() {
+        let curve = build(CurveFlags::empty(), 6);
+        let style = SvgStyle {
+            background: Some([0x12, 0x34, 0x56]),
+            stroke_width: 2.0,
+            rotation: 30.0,
+            dash: StrokeDash::Dashed,
+            markers: Some(3.0),
+            ..SvgStyle::default()
+        };
+        let mut plain = String::new();
+        curve.write_svg_styled(100.0, style, &mut plain).unwrap();
+        let mut colored = String::new();
+        curve
+            .write_svg_styled_colored(
+                100.0,
+                style,
+                &mut |i, _, _| [i as u8 % 2 * 255; 3],
+                &mut colored,
+            )
+            .unwrap();
+        // everything but the paths themselves is the same
+        let prefix = |svg: &str| svg[..svg.find("<path").unwrap()].to_owned();
+        assert_eq!(prefix(&plain), prefix(&colored));
+        assert!(colored.contains(r##"fill="#123456""##));
+        assert!(colored.ends_with("</g></svg>"));
+        assert_eq!(colored.matches("<circle").count(), 2);
+        assert_eq!(colored.matches("<path").count(), curve.len());
+        assert_eq!(
+            colored.matches("stroke-width:2;stroke-dasharray").count(),
+            curve.len()
+        );
+        assert_eq!(svg_segments(&colored), curve.len());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips() {
+        for flags in ALL_FLAGS {
+            for depth in [0, 1, 5, 12] {
+                for start in [Np0, Nmp] {
+                    let curve = DragonCurve::builder()
+                        .start(start)
+                        .flags(flags)
+                        .depth(depth)
+                        .build();
+                    let json = serde_json::to_string(&curve).unwrap();
+                    let back: DragonCurve = serde_json::from_str(&json).unwrap();
+                    assert_eq!(back.list(), curve.list(), "{json}");
+                    assert_eq!((back.depth(), back.flags()), (depth, flags));
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_unbuildable_curves() {
+        let json = serde_json::to_string(&build(CurveFlags::DRAGON, 1)).unwrap();
+        assert!(json.contains(r#""depth":1"#), "{json}");
+        for depth in [max_depth(DESERIALIZE_BUDGET) + 1, 40, 255] {
+            let deep = json.replace(r#""depth":1"#, &format!(r#""depth":{depth}"#));
+            let err = serde_json::from_str::<DragonCurve>(&deep).unwrap_err();
+            assert!(err.to_string().contains("budget"), "{err}");
+        }
+        let mut custom = build(CurveFlags::DRAGON, 2);
+        custom.append(&build(CurveFlags::DRAGON, 1));
+        let json = serde_json::to_string(&custom).unwrap();
+        let err = serde_json::from_str::<DragonCurve>(&json).unwrap_err();
+        assert!(err.to_string().contains("custom"), "{err}");
+    }
+
+    #[test]
+    fn from_folds_matches_builder() {
+        let folds = |s| DragonCurve::from_folds(Np0, s, 1 << 16).unwrap();
+        let rrr = folds("RRR");
+        assert_eq!(rrr.list(), build(CurveFlags::DRAGON, 3).list());
+        assert_eq!((rrr.depth(), rrr.flags()), (3, CurveFlags::DRAGON));
+        let lll = folds("LLL");
+        assert_eq!(lll.list(), build(CurveFlags::FLIP, 3).list());
+        assert_eq!((lll.depth(), lll.flags()), (3, CurveFlags::FLIP));
+        let mut mixed = folds("RLR");
+        assert_eq!((mixed.len(), mixed.flags()), (8, CurveFlags::CUSTOM));
+        let before = mixed.list().to_vec();
+        mixed.set_depth(5);
+        assert_eq!(mixed.list(), before);
+    }
+
+    #[test]
+    fn from_folds_rejects_bad_input() {
+        assert_eq!(
+            DragonCurve::from_folds(Np0, "RRxL", 1 << 16).unwrap_err(),
+            InvalidFold::Char {
+                index: 2,
+                found: 'x'
+            }
+        );
+        assert_eq!(
+            DragonCurve::from_folds(Np0, &"R".repeat(40), 1 << 16).unwrap_err(),
+            InvalidFold::TooMany {
+                folds: 40,
+                budget: 1 << 16
+            }
+        );
+        assert!(DragonCurve::from_folds(Np0, &"L".repeat(300), usize::MAX).is_err());
+        assert!(DragonCurve::from_folds(Np0, &"R".repeat(16), 1 << 16).is_ok());
+    }
+
+    #[test]
+    fn svg_path_round_trips() {
+        let d_attr = |svg: &str| {
+            let rest = &svg[svg.find(r#" d=""#).unwrap() + 4..];
+            rest[..rest.find('"').unwrap()].to_owned()
+        };
+        for flags in ALL_FLAGS {
+            for depth in [0, 1, 2, 5, 9] {
+                let curve = build(flags, depth);
+                let parsed =
+                    DragonCurve::from_svg_path(&curve.path_data(curve.step(100.0))).unwrap();
+                assert_eq!(parsed.list(), curve.list(), "{flags:?} at depth {depth}");
+                assert_eq!(parsed.depth(), depth);
+                for render in [RenderStyle::Diagonal45, RenderStyle::Sharp90] {
+                    let mut svg = String::new();
+                    let style = SvgStyle {
+                        render,
+                        ..SvgStyle::default()
+                    };
+                    curve.write_svg_styled(100.0, style, &mut svg).unwrap();
+                    let parsed = DragonCurve::from_svg_path(&d_attr(&svg)).unwrap();
+                    let drawn = curve.segments_styled(render).collect::<Vec<_>>();
+                    assert_eq!(
+                        parsed.list(),
+                        drawn,
+                        "{flags:?} at depth {depth} in {render:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn svg_path_rejects_other_lengths() {
+        assert_eq!(
+            DragonCurve::from_svg_path("M0 0 h10 h5").unwrap_err(),
+            InvalidPath::Delta {
+                index: 10,
+                x: 5.0,
+                y: 0.0
+            }
+        );
+        assert_eq!(
+            DragonCurve::from_svg_path("M0 0 h10 l10 10").unwrap_err(),
+            InvalidPath::Delta {
+                index: 10,
+                x: 10.0,
+                y: 10.0
+            }
+        );
+    }
+
+    #[test]
+    fn io_writers_match_strings() {
+        let curve = build(CurveFlags::DRAGON, 16);
+        let mut expected = String::new();
+        curve.write_svg(100.0, &mut expected).unwrap();
+        let path = std::env::temp_dir().join(format!("dragons_io_{}.svg", std::process::id()));
+        let mut file = io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        curve.write_svg_io(100.0, &mut file).unwrap();
+        drop(file);
+        let written = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(written == expected.as_bytes());
+
+        // every other exporter streams through the same adapter
+        let mut expected = String::new();
+        curve.write_eps(100.0, &mut expected).unwrap();
+        let mut adapter = IoAdapter::new(Vec::new());
+        let res = curve.write_eps(100.0, &mut adapter);
+        adapter.finish(res).unwrap();
+        assert!(adapter.inner == expected.as_bytes());
+    }
+
+    /// Deep enough that the `rayon` feature folds the last levels in parallel
+    #[test]
+    fn deep_folds_match_reference() {
+        for flags in ALL_FLAGS {
+            for depth in [15, 16] {
+                #[cfg(feature = "rayon")]
+                assert!(1 << (depth - 1) >= PAR_FOLD_THRESHOLD);
+                let mut expected = Vec::new();
+                reference((0.0, 0.0), (1.0, 0.0), depth, flags, &mut expected);
+                let curve = build(flags, depth);
+                assert!(curve.list() == expected, "{flags:?} at depth {depth}");
+            }
+        }
+    }
+
+    fn trapezoid_area(traps: &[[(f32, f32); 4]]) -> f32 {
+        traps
+            .iter()
+            .map(|&[(l0, y0), (r0, _), (r1, y1), (l1, _)]| (r0 - l0 + r1 - l1) * 0.5 * (y1 - y0))
+            .sum()
+    }
+
+    #[test]
+    fn fill_trapezoids_of_a_square() {
+        // down, right, up, right, closed back along the top
+        let curve = build(CurveFlags::DRAGON, 2);
+        assert_eq!(curve.list(), [N0p, Np0, N0m, Np0]);
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let traps = curve.fill_trapezoids(25.0, RenderStyle::Diagonal45, rule);
+            assert_eq!(
+                traps,
+                [[(0.0, 0.0), (25.0, 0.0), (25.0, 25.0), (0.0, 25.0)]]
+            );
+        }
+    }
+
+    #[test]
+    fn fill_trapezoids_cover_the_inside() {
+        // while the outline doesn't cross itself, the fill is exactly the polygon
+        for (flags, simple) in [
+            (CurveFlags::DRAGON, 2),
+            (CurveFlags::FLIP, 2),
+            (CurveFlags::LEVY, 4),
+            (CurveFlags::LEVY | CurveFlags::FLIP, 4),
+        ] {
+            for depth in 1..=simple {
+                let curve = build(flags, depth);
+                let traps = curve.fill_trapezoids(
+                    curve.step(100.0),
+                    RenderStyle::Diagonal45,
+                    FillRule::NonZero,
+                );
+                let area = trapezoid_area(&traps);
+                let expected = curve.signed_area(100.0).abs();
+                assert!(
+                    (area - expected).abs() < 1e-2,
+                    "{flags:?} at depth {depth}: {area} != {expected}"
+                );
+            }
+        }
+        // past that, compare against the winding number sampled on a fine grid
+        for flags in ALL_FLAGS {
+            for depth in 3..=7 {
+                let curve = build(flags, depth);
+                let step = curve.step(100.0);
+                let points = curve.points(step, (0.0, 0.0));
+                let winding = |(x, y): (f32, f32)| {
+                    let mut w = 0;
+                    for (&a, &b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                        let cross = (b.0 - a.0) * (y - a.1) - (x - a.0) * (b.1 - a.1);
+                        if a.1 <= y && b.1 > y && cross > 0.0 {
+                            w += 1;
+                        } else if b.1 <= y && a.1 > y && cross < 0.0 {
+                            w -= 1;
+                        }
+                    }
+                    w
+                };
+                let (min_x, min_y, max_x, max_y) = curve.bounds(step);
+                let cell = step / 12.3;
+                let (nx, ny) = (
+                    ((max_x - min_x) / cell) as usize + 1,
+                    ((max_y - min_y) / cell) as usize + 1,
+                );
+                let mut counts = [0; 2];
+                for i in 0..nx {
+                    for j in 0..ny {
+                        let p = (
+                            min_x + (i as f32 + 0.3719) * cell,
+                            min_y + (j as f32 + 0.6181) * cell,
+                        );
+                        let w = winding(p);
+                        counts[0] += (w != 0) as usize;
+                        counts[1] += (w % 2 != 0) as usize;
+                    }
+                }
+                for (rule, count) in [FillRule::NonZero, FillRule::EvenOdd]
+                    .into_iter()
+                    .zip(counts)
+                {
+                    let area =
+                        trapezoid_area(&curve.fill_trapezoids(step, RenderStyle::Diagonal45, rule));
+                    let sampled = count as f32 * cell * cell;
+                    assert!(
+                        (area - sampled).abs() < sampled * 0.03,
+                        "{flags:?} at depth {depth} with {rule:?}: {area} != {sampled}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn self_intersections_by_kind() {
+        for flags in [CurveFlags::DRAGON, CurveFlags::FLIP] {
+            for depth in 0..=14 {
+                assert_eq!(
+                    build(flags, depth).self_intersections(),
+                    0,
+                    "{flags:?} at depth {depth}"
+                );
+            }
+        }
+        for flags in [CurveFlags::LEVY, CurveFlags::LEVY | CurveFlags::FLIP] {
+            let counts = (0..=8)
+                .map(|depth| build(flags, depth).self_intersections())
+                .collect::<Vec<_>>();
+            assert_eq!(counts, [0, 0, 0, 0, 1, 3, 7, 15, 33], "{flags:?}");
+        }
+    }
+
+    #[test]
+    fn packed_dirs_round_trip() {
+        // 11 directions end partway through the fifth byte
+        for len in [0usize, 1, 8, 11, 64] {
+            let dirs = (0..len)
+                .map(|i| Dir::from_u8((i * 5 % 8) as u8))
+                .collect::<Vec<_>>();
+            let packed = dirs.iter().copied().collect::<PackedDirs>();
+            assert_eq!(packed.as_bytes().len(), (len * 3).div_ceil(8));
+            let back = PackedDirs::from_bytes(len, packed.as_bytes()).unwrap();
+            assert_eq!(back, packed);
+            assert!(back.iter().eq(dirs.iter().copied()), "{len} directions");
+        }
+        // stray bits past the end don't leak into later pushes
+        let mut back = PackedDirs::from_bytes(2, &[0xff, 0xff, 0xff]).unwrap();
+        assert_eq!(back.as_bytes(), [0x3f]);
+        back.push(Npp);
+        assert!(back.iter().eq([N0p, N0p, Npp]));
+        assert_eq!(
+            PackedDirs::from_bytes(11, &[0; 4]).unwrap_err(),
+            ShortBuffer {
+                len: 11,
+                needed: 5,
+                found: 4
+            }
+        );
+        assert!(PackedDirs::from_bytes(usize::MAX, &[0; 4]).is_err());
+    }
+
+    #[test]
+    fn set_flags_flip_matches_build() {
+        for kind in [CurveFlags::DRAGON, CurveFlags::LEVY] {
+            for depth in [0, 1, 2, 3, 6, 9] {
+                for start in [Np0, Npm, N0p] {
+                    let fresh = |flags| {
+                        DragonCurve::builder()
+                            .start(start)
+                            .flags(flags)
+                            .depth(depth)
+                            .build()
+                    };
+                    let mut curve = fresh(kind);
+                    curve.set_flags(kind | CurveFlags::FLIP);
+                    assert_eq!(curve.list(), fresh(kind | CurveFlags::FLIP).list());
+                    assert_eq!(curve.start(), start);
+                    curve.set_flags(kind);
+                    assert_eq!(
+                        curve.list(),
+                        fresh(kind).list(),
+                        "{kind:?} from {start:?} at depth {depth}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flip_mirrors_across_the_chord() {
+        for kind in [CurveFlags::DRAGON, CurveFlags::LEVY] {
+            for start in Dir::ALL {
+                // reflect across the line through the start in the starting direction
+                let (ax, ay) = start.as_vec();
+                let norm = ax.hypot(ay);
+                let (ux, uy) = (ax / norm, ay / norm);
+                let reflect = |(x, y): (f32, f32)| {
+                    let along = x * ux + y * uy;
+                    (2.0 * along * ux - x, 2.0 * along * uy - y)
+                };
+                for depth in 0..=8 {
+                    let curve = |flags| {
+                        DragonCurve::builder()
+                            .start(start)
+                            .flags(flags)
+                            .depth(depth)
+                            .build()
+                            .points(1.0, (0.0, 0.0))
+                    };
+                    let flipped = curve(kind | CurveFlags::FLIP);
+                    for (i, (p, q)) in curve(kind).into_iter().zip(flipped).enumerate() {
+                        assert!(
+                            close(reflect(p), q),
+                            "{kind:?} from {start:?} at depth {depth}, point {i}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn display_turns() {
+        assert_eq!(build(CurveFlags::DRAGON, 2).to_string(), "→: F-F-F+F");
+        for flags in ALL_FLAGS {
+            let curve = build(flags, 5);
+            let shown = curve.to_string();
+            assert!(
+                shown.starts_with(&format!("{}: F", curve.start())),
+                "{shown}"
+            );
+            assert_eq!(shown.matches('F').count(), curve.len());
+            assert!(!shown.contains(['<', '>']), "{shown}");
+        }
+        let custom = DragonCurve {
+            list: vec![Np0, Npp, Nmm, Nm0, Np0],
+            depth: 2,
+            flags: CurveFlags::CUSTOM,
+        };
+        assert_eq!(custom.to_string(), "→: F<F--F>F--F");
+    }
+
+    #[test]
+    fn twin_tiles_the_lattice() {
+        let edge = |a: (i32, i32), b: (i32, i32)| (a.min(b), a.max(b));
+        for flags in [CurveFlags::DRAGON, CurveFlags::FLIP] {
+            for depth in [2, 4, 6, 8] {
+                let curve = build(flags, depth);
+                let walk = curve.cell_walk().unwrap().collect::<Vec<_>>();
+                let end = walk[walk.len() - 1];
+                let twin = curve
+                    .twin()
+                    .cell_walk()
+                    .unwrap()
+                    .map(|(x, y)| (x + end.0, y + end.1))
+                    .collect::<Vec<_>>();
+                assert_eq!(twin[0], end);
+                assert_eq!(twin[twin.len() - 1], (0, 0));
+                let pair = walk
+                    .windows(2)
+                    .chain(twin.windows(2))
+                    .map(|w| edge(w[0], w[1]))
+                    .collect::<Vec<_>>();
+                let mut covered = HashSet::new();
+                for a in -8..=8 {
+                    for b in -8..=8 {
+                        let (dx, dy) = (a * end.0 - b * end.1, a * end.1 + b * end.0);
+                        for &(p, q) in &pair {
+                            let moved = ((p.0 + dx, p.1 + dy), (q.0 + dx, q.1 + dy));
+                            assert!(
+                                covered.insert(moved),
+                                "{flags:?} at depth {depth} overlaps at {moved:?}"
+                            );
+                        }
+                    }
+                }
+                // well inside the translated copies, every edge is covered
+                let reach = 2 * end.0.abs().max(end.1.abs());
+                for x in -reach..reach {
+                    for y in -reach..=reach {
+                        assert!(
+                            covered.contains(&((x, y), (x + 1, y))),
+                            "{flags:?} at depth {depth}"
+                        );
+                        assert!(
+                            covered.contains(&((y, x), (y, x + 1))),
+                            "{flags:?} at depth {depth}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn offsets_follow_deltas() {
+        let offsets = Dir::ALL.map(|d| d.offset());
+        assert_eq!(
+            offsets,
+            [
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn set_depth_round_trips() {
+        for flags in ALL_FLAGS {
+            for start in [Np0, Npp, N0m] {
+                for from in 0..=12 {
+                    let curve = DragonCurve::builder()
+                        .start(start)
+                        .flags(flags)
+                        .depth(from)
+                        .build();
+                    for to in 0..=12 {
+                        let mut changed = curve.clone();
+                        changed.set_depth(to);
+                        let fresh = DragonCurve::builder()
+                            .start(start)
+                            .flags(flags)
+                            .depth(to)
+                            .build();
+                        assert_eq!(changed, fresh, "{flags:?} from {start:?}, {from} to {to}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn set_depth_up_then_down() {
+        for flags in [CurveFlags::DRAGON, CurveFlags::FLIP, CurveFlags::LEVY] {
+            let mut curve = build(flags, 1);
+            curve.set_depth(4);
+            curve.set_depth(2);
+            assert_eq!(curve.depth(), 2);
+            assert_eq!(curve.start(), Np0);
+            assert_eq!(curve, build(flags, 2), "{flags:?}");
+        }
+        let mut curve = build(CurveFlags::DRAGON, 2);
+        curve.set_depth(4);
+        curve.set_depth(2);
+        assert_eq!(curve.list(), [N0p, Np0, N0m, Np0]);
     }
 }
@@ -1,106 +1,467 @@
-#![feature(linked_list_cursors, linked_list_retain)]
-use dragon::{CurveFlags, DragonCurve};
+use dragon::{
+    CurveFlags, Dir, DragonCurve, DrawSink, FillRule, RenderStyle, StrokeDash, SvgStyle, Transform,
+};
 use eframe::egui;
-use eframe::egui::epaint::PathStroke;
 use std::cmp::Ordering;
+use std::collections::HashSet;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 mod dragon;
+#[cfg(not(target_arch = "wasm32"))]
+mod raster;
+#[cfg(not(target_arch = "wasm32"))]
+mod terdragon;
 
-trait MakeStroke {
-    fn stroke(&mut self) -> PathStroke;
+/// Picks the color of each segment of a curve as it's drawn
+trait ColorMode {
+    /// Color of segment `index` out of `total`, going in direction `dir`, with its midpoint at `pos`.
+    ///
+    /// `total` counts segments, as in [`DragonCurve::len`], so `index` is always below it.
+    fn color(&mut self, index: usize, total: usize, dir: Dir, pos: egui::Pos2) -> egui::Color32;
 }
-impl MakeStroke for (f32, egui::Color32) {
-    fn stroke(&mut self) -> PathStroke {
-        (*self).into()
+impl ColorMode for egui::Color32 {
+    fn color(&mut self, _: usize, _: usize, _: Dir, _: egui::Pos2) -> egui::Color32 {
+        *self
     }
 }
-impl MakeStroke for egui::Stroke {
-    fn stroke(&mut self) -> PathStroke {
-        (*self).into()
+
+/// Offsets a position in `0..=1` along a gradient by `phase`, wrapping back around past the end
+fn shift_phase(t: f64, phase: f64) -> f64 {
+    let t = t + phase;
+    if t > 1.0 { t - 1.0 } else { t }
+}
+
+/// Packs a position in `0..=1` along a gradient into an opaque color. It's split between red and
+/// green, so their ratio survives the tessellator fading out thin lines.
+fn encode_position(t: f64) -> egui::Color32 {
+    let g = (t.clamp(0.0, 1.0) * 255.0).round() as u8;
+    egui::Color32::from_rgb(255 - g, g, 0)
+}
+
+/// Paints a color packed by [`encode_position`] with its place along `grad`, offset by `phase`,
+/// keeping whatever coverage the tessellator gave it
+fn paint_position(color: egui::Color32, grad: colorous::Gradient, phase: f64) -> egui::Color32 {
+    let sum = color.r() as u16 + color.g() as u16;
+    if sum == 0 {
+        return egui::Color32::TRANSPARENT;
     }
+    let t = color.g() as f64 / sum as f64;
+    let (r, g, b) = grad.eval_continuous(shift_phase(t, phase)).into_tuple();
+    egui::Color32::from_rgb(r, g, b).gamma_multiply(color.a() as f32 / 255.0)
 }
 
 struct GradientStroke {
-    width: f32,
-    count: usize,
-    max: usize,
-    grad: colorous::Gradient,
-}
-impl MakeStroke for GradientStroke {
-    fn stroke(&mut self) -> PathStroke {
-        let (r, g, b) = self
-            .grad
-            .eval_rational(std::cmp::min(self.count, self.max), self.max)
-            .into_tuple();
-        if self.count < self.max {
-            self.count += 1;
-        }
-        (self.width, egui::Color32::from_rgb(r, g, b)).into()
+    /// `None` packs the position along the gradient into the color instead, see
+    /// [`encode_position`]
+    grad: Option<colorous::Gradient>,
+    /// Offset along the gradient, in `0..1`
+    phase: f64,
+}
+impl ColorMode for GradientStroke {
+    fn color(&mut self, index: usize, total: usize, _: Dir, _: egui::Pos2) -> egui::Color32 {
+        let Some(grad) = self.grad else {
+            return encode_position(index as f64 / total as f64);
+        };
+        let color = if self.phase == 0.0 {
+            grad.eval_rational(index, total)
+        } else {
+            grad.eval_continuous(shift_phase(index as f64 / total as f64, self.phase))
+        };
+        let (r, g, b) = color.into_tuple();
+        egui::Color32::from_rgb(r, g, b)
     }
 }
 
+/// A color taking up `weight` of a flag, relative to the other bands
+type Band = (egui::Color32, f32);
+
+/// Color of the last band, which is also what's left past the end of every other one. With no
+/// bands at all there's nothing to draw with, so this is transparent.
+fn last_band(colors: &[Band]) -> egui::Color32 {
+    debug_assert!(!colors.is_empty(), "no bands to color with");
+    colors.last().map_or(egui::Color32::TRANSPARENT, |b| b.0)
+}
+
 struct GradientBands<'a> {
-    width: f32,
-    count: usize,
-    max: usize,
-    colors: &'a [egui::Color32],
-}
-impl MakeStroke for GradientBands<'_> {
-    fn stroke(&mut self) -> PathStroke {
-        let ratio = (self.count * (self.colors.len() - 1)) as f32 / self.max as f32;
-        let idx = ratio as usize;
-        let frac = ratio.fract();
-        let color = if frac < f32::EPSILON {
-            self.colors[idx]
-        } else {
-            self.colors[idx].lerp_to_gamma(self.colors[idx + 1], frac)
+    colors: &'a [Band],
+}
+impl ColorMode for GradientBands<'_> {
+    fn color(&mut self, index: usize, total: usize, _: Dir, _: egui::Pos2) -> egui::Color32 {
+        let (Some(first), Some(last)) = (self.colors.first(), self.colors.last()) else {
+            return last_band(self.colors);
         };
-        if self.count < self.max - 1 {
-            self.count += 1;
+        // each color sits at the middle of its band, measured from the middle of the first one,
+        // so the first and last colors land exactly on the ends of the curve, or a single color
+        // covers all of it
+        let span = self.colors.iter().map(|b| b.1).sum::<f32>() - (first.1 + last.1) * 0.5;
+        let pos = index as f32 * span / total as f32;
+        let mut center = 0.0;
+        for pair in self.colors.windows(2) {
+            let next = center + (pair[0].1 + pair[1].1) * 0.5;
+            if pos < next {
+                let frac = (pos - center) / (next - center);
+                return if frac < f32::EPSILON {
+                    pair[0].0
+                } else {
+                    pair[0].0.lerp_to_gamma(pair[1].0, frac)
+                };
+            }
+            center = next;
         }
-        (self.width, color).into()
+        last.0
     }
 }
 struct SolidBands<'a> {
+    colors: &'a [Band],
+}
+impl ColorMode for SolidBands<'_> {
+    fn color(&mut self, index: usize, total: usize, _: Dir, _: egui::Pos2) -> egui::Color32 {
+        let mut pos = index as f32 * self.colors.iter().map(|b| b.1).sum::<f32>() / total as f32;
+        for &(color, weight) in self.colors {
+            if pos < weight {
+                return color;
+            }
+            pos -= weight;
+        }
+        last_band(self.colors)
+    }
+}
+/// Colors segments by how far their midpoint is from `origin`, relative to `max`
+struct RadiusStroke {
+    origin: egui::Pos2,
+    max: f32,
+    /// `None` packs the position along the gradient into the color instead, like
+    /// [`GradientStroke::grad`]
+    grad: Option<colorous::Gradient>,
+    /// Offset along the gradient, in `0..1`
+    phase: f64,
+}
+impl ColorMode for RadiusStroke {
+    fn color(&mut self, _: usize, _: usize, _: Dir, pos: egui::Pos2) -> egui::Color32 {
+        let dist = pos.distance(self.origin);
+        let t = if self.max > 0.0 {
+            (dist / self.max).min(1.0)
+        } else {
+            0.0
+        };
+        let Some(grad) = self.grad else {
+            return encode_position(t as f64);
+        };
+        let (r, g, b) = grad
+            .eval_continuous(shift_phase(t as f64, self.phase))
+            .into_tuple();
+        egui::Color32::from_rgb(r, g, b)
+    }
+}
+/// Collects a curve as egui shapes, joining runs of segments with the same color into a single
+/// polyline so thick lines meet cleanly at the turns
+struct EguiDraw<'a> {
+    shapes: &'a mut Vec<egui::Shape>,
+    pos: egui::Pos2,
+    /// Rotation applied to every segment on screen
+    rot: egui::emath::Rot2,
     width: f32,
-    count: usize,
-    max: usize,
-    colors: &'a [egui::Color32],
-}
-impl MakeStroke for SolidBands<'_> {
-    fn stroke(&mut self) -> PathStroke {
-        let idx = (self.count * self.colors.len()) / self.max;
-        if self.count < self.max - 1 {
-            self.count += 1;
+    mode: &'a mut dyn ColorMode,
+    /// Index of the segment being drawn
+    index: usize,
+    /// Number of segments in the curve
+    total: usize,
+    /// Direction of the segment being drawn
+    dir: Dir,
+    /// Points of the polyline being built, all in `run_color`, ending at `pos`
+    run: Vec<egui::Pos2>,
+    run_color: egui::Color32,
+    /// Lengths of each dash and gap, or `None` for a solid line
+    dash: Option<[f32; 2]>,
+    /// Distance into the current dash and gap, carried over from one polyline to the next
+    dash_phase: f32,
+}
+impl dragon::Draw for EguiDraw<'_> {
+    type Output = ();
+
+    fn line(&mut self, x: f32, y: f32) {
+        let old = self.pos;
+        self.pos += self.rot * egui::vec2(x, y);
+        let color = self
+            .mode
+            .color(self.index, self.total, self.dir, old.lerp(self.pos, 0.5));
+        self.extend_run(old, [self.pos], color);
+        self.index += 1;
+    }
+    fn move_by(&mut self, x: f32, y: f32) {
+        self.flush_run();
+        self.pos += self.rot * egui::vec2(x, y);
+    }
+    fn quad(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        // corners are short, so a handful of points is enough to look smooth
+        const SAMPLES: usize = 8;
+        let start = self.pos;
+        let ctrl = self.rot * egui::vec2(cx, cy);
+        let end = self.rot * egui::vec2(x, y);
+        let points = (0..=SAMPLES).map(|i| {
+            let t = i as f32 / SAMPLES as f32;
+            start + ctrl * (2.0 * t * (1.0 - t)) + end * (t * t)
+        });
+        self.pos = start + end;
+        let color = self
+            .mode
+            .color(self.index, self.total, self.dir, start + ctrl);
+        self.extend_run(start, points, color);
+    }
+}
+impl EguiDraw<'_> {
+    /// Longest polyline to build before starting another, so that the tessellator can still skip
+    /// the parts of a zoomed-in curve that are off screen
+    const MAX_RUN: usize = 256;
+
+    /// Continue the current polyline from `from` through `points`, or start a new one if the color
+    /// changed. `points` may start with `from` itself.
+    fn extend_run(
+        &mut self,
+        from: egui::Pos2,
+        points: impl IntoIterator<Item = egui::Pos2>,
+        color: egui::Color32,
+    ) {
+        if color != self.run_color || self.run.len() >= Self::MAX_RUN {
+            let joined = !self.run.is_empty();
+            self.flush_run();
+            // separate polylines only meet edge to edge, so fill in the outside of the turn.
+            // Overlapping translucent strokes would show as a darker dot, so those are left be,
+            // and so are joints that fall in the gap of a dashed line
+            let in_dash = self.dash.is_none_or(|[on, _]| self.dash_phase < on);
+            if joined && in_dash && self.width > 1.5 && color.is_opaque() {
+                self.shapes
+                    .push(egui::Shape::circle_filled(from, self.width * 0.5, color));
+            }
+            self.run_color = color;
+            self.run.push(from);
         }
-        (self.width, self.colors[idx]).into()
+        self.run
+            .extend(points.into_iter().skip_while(|&p| p == from));
+    }
+    /// Finish the current polyline, if there is one
+    fn flush_run(&mut self) {
+        if self.run.len() >= 2 {
+            let stroke = (self.width, self.run_color);
+            match self.dash {
+                None => self
+                    .shapes
+                    .push(egui::Shape::line(std::mem::take(&mut self.run), stroke)),
+                Some(pattern) => {
+                    for dash in split_dashes(&self.run, pattern, &mut self.dash_phase) {
+                        self.shapes.push(egui::Shape::line(dash, stroke));
+                    }
+                }
+            }
+        }
+        self.run.clear();
+    }
+    /// Draw the first `count` segments of `curve` in the given style, starting from the current
+    /// position.
+    ///
+    /// If `radius` is positive, corners are rounded off with that radius.
+    fn draw_curve(
+        &mut self,
+        curve: &DragonCurve,
+        step: f32,
+        style: RenderStyle,
+        radius: f32,
+        count: usize,
+    ) {
+        self.total = curve.len();
+        let mut prev = None;
+        let mut segs = curve.segments_styled(style).take(count).peekable();
+        while let Some(seg) = segs.next() {
+            self.dir = seg;
+            if radius > 0.0 {
+                seg.draw_rounded(prev, segs.peek().copied(), self, step, radius);
+            } else {
+                seg.draw(self, step);
+            }
+            prev = Some(seg);
+        }
+        self.flush_run();
     }
 }
-struct EguiDraw<'a, S> {
+
+/// Split the polyline through `points` into dashes `on` long with gaps `off` long between them,
+/// starting `phase` into the pattern and leaving `phase` where it ends.
+///
+/// Dashes bend around the corners of the polyline instead of cutting across them.
+fn split_dashes(
+    points: &[egui::Pos2],
+    [on, off]: [f32; 2],
+    phase: &mut f32,
+) -> Vec<Vec<egui::Pos2>> {
+    let mut out = Vec::new();
+    let mut dash = Vec::new();
+    if *phase < on {
+        dash.push(points[0]);
+    }
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let len = a.distance(b);
+        let mut t = 0.0;
+        while t < len {
+            let in_dash = *phase < on;
+            let boundary = if in_dash { on } else { on + off };
+            let left = boundary - *phase;
+            if left > len - t {
+                // the segment ends first, leaving a corner in the dash if there is one
+                *phase += len - t;
+                t = len;
+                if in_dash {
+                    dash.push(b);
+                }
+                continue;
+            }
+            t += left;
+            let p = a.lerp(b, t / len);
+            if in_dash {
+                *phase = on;
+                dash.push(p);
+                out.push(std::mem::take(&mut dash));
+            } else {
+                *phase = 0.0;
+                dash.push(p);
+            }
+        }
+    }
+    if dash.len() >= 2 {
+        out.push(dash);
+    }
+    out
+}
+
+/// Everything that changes how the curve is stroked, so its tessellated mesh can be reused from
+/// one frame to the next until one of these changes.
+///
+/// Turning the view and shifting the gradient's phase are left out, since those are applied to the
+/// cached mesh as it's painted.
+#[derive(PartialEq)]
+struct CurveKey {
+    depth: u8,
+    flags: CurveFlags,
+    start: Dir,
+    coloring: Coloring,
+    gradient: GradientKind,
+    pride_flag: PrideFlag,
+    custom_stops: Vec<Band>,
+    /// Whether the mesh holds positions along the gradient instead of colors, to be painted in at
+    /// each frame's phase
+    positions: bool,
+    fg: egui::Color32,
+    width: f32,
+    /// Point the view turns around
+    center: egui::Pos2,
+    step: f32,
+    /// Area outside of which the curve is skipped, covering the view however it's turned
+    cull: egui::Rect,
+    render_style: RenderStyle,
+    corner_radius: f32,
+    shown: usize,
+    stroke_dash: StrokeDash,
+    /// Color and rule of the fill drawn instead of the stroke, if there is one
+    fill: Option<(egui::Color32, FillRule)>,
+    /// Lines are feathered by a pixel, so the mesh also depends on the display scale
+    pixels_per_point: f32,
+}
+
+/// Everything that changes how the attractor is stroked, like [`CurveKey`] for the curve itself
+#[derive(PartialEq)]
+struct AttractorKey {
+    flags: CurveFlags,
+    start: Dir,
+    pos: egui::Pos2,
+    size: f32,
+    rot: egui::emath::Rot2,
+    width: f32,
+    render_style: RenderStyle,
+    color: egui::Color32,
+    pixels_per_point: f32,
+}
+
+/// Labels the midpoint of every `every`th segment with its index, skipping labels that would overlap
+struct LabelDraw<'a> {
     painter: &'a egui::Painter,
     pos: egui::Pos2,
-    stroke: S,
+    /// Rotation applied to every segment on screen
+    rot: egui::emath::Rot2,
+    index: usize,
+    every: usize,
+    font: egui::FontId,
+    color: egui::Color32,
+    /// Cells, one font size across, already covered by a label
+    taken: HashSet<(i32, i32)>,
 }
-impl<S: MakeStroke> dragon::Draw for EguiDraw<'_, S> {
+impl dragon::Draw for LabelDraw<'_> {
     type Output = ();
 
     fn line(&mut self, x: f32, y: f32) {
         let old = self.pos;
-        self.pos += egui::vec2(x, y);
-        self.painter
-            .line_segment([old, self.pos], self.stroke.stroke());
+        self.pos += self.rot * egui::vec2(x, y);
+        if self.index.is_multiple_of(self.every) {
+            let text = self.index.to_string();
+            let galley = self
+                .painter
+                .layout_no_wrap(text, self.font.clone(), self.color);
+            let rect =
+                egui::Align2::CENTER_CENTER.anchor_size(old.lerp(self.pos, 0.5), galley.size());
+            let cell = self.font.size;
+            let (x0, y0) = (
+                (rect.min.x / cell).floor() as i32,
+                (rect.min.y / cell).floor() as i32,
+            );
+            let (x1, y1) = (
+                (rect.max.x / cell).floor() as i32,
+                (rect.max.y / cell).floor() as i32,
+            );
+            let cells = (x0..=x1).flat_map(|x| (y0..=y1).map(move |y| (x, y)));
+            if !cells.clone().any(|c| self.taken.contains(&c)) {
+                self.taken.extend(cells);
+                self.painter.galley(rect.min, galley, self.color);
+            }
+        }
+        self.index += 1;
     }
-    fn horiz(&mut self, x: f32) {
-        let old = self.pos.x;
-        self.pos.x += x;
-        self.painter
-            .hline(old..=self.pos.x, self.pos.y, self.stroke.stroke());
+    fn move_by(&mut self, x: f32, y: f32) {
+        self.pos += self.rot * egui::vec2(x, y);
     }
-    fn vert(&mut self, y: f32) {
-        let old = self.pos.y;
-        self.pos.y += y;
-        self.painter
-            .vline(self.pos.x, old..=self.pos.y, self.stroke.stroke());
+}
+
+/// Renders each segment as a quad into a mesh, with the color running from `edge` to `center` across its width
+struct MeshDraw {
+    mesh: egui::Mesh,
+    pos: egui::Pos2,
+    /// Rotation applied to every segment on screen
+    rot: egui::emath::Rot2,
+    width: f32,
+    edge: egui::Color32,
+    center: egui::Color32,
+}
+impl dragon::Draw for MeshDraw {
+    type Output = ();
+
+    fn line(&mut self, x: f32, y: f32) {
+        let old = self.pos;
+        let delta = self.rot * egui::vec2(x, y);
+        self.pos += delta;
+        let normal = delta.normalized().rot90() * (self.width * 0.5);
+        let idx = self.mesh.vertices.len() as u32;
+        for p in [old, self.pos] {
+            self.mesh.colored_vertex(p + normal, self.edge);
+            self.mesh.colored_vertex(p, self.center);
+            self.mesh.colored_vertex(p - normal, self.edge);
+        }
+        for i in 0..2 {
+            self.mesh.add_triangle(idx + i, idx + i + 1, idx + i + 3);
+            self.mesh
+                .add_triangle(idx + i + 1, idx + i + 4, idx + i + 3);
+        }
+    }
+    fn move_by(&mut self, x: f32, y: f32) {
+        self.pos += self.rot * egui::vec2(x, y);
     }
 }
 
@@ -113,7 +474,7 @@ enum GradientKind {
     Sinebow,
 }
 impl GradientKind {
-    fn into_colorous(&self) -> colorous::Gradient {
+    fn into_colorous(self) -> colorous::Gradient {
         match self {
             Self::Viridis => colorous::VIRIDIS,
             Self::Plasma => colorous::PLASMA,
@@ -128,12 +489,22 @@ impl GradientKind {
 enum PrideFlag {
     Rainbow,
     Trans,
+    Bisexual,
+    Pansexual,
+    Nonbinary,
+    Lesbian,
+    Progress,
 }
 impl PrideFlag {
-    fn into_bands(&self) -> &'static [egui::Color32] {
+    fn into_bands(self) -> &'static [Band] {
         match self {
             Self::Rainbow => RAINBOW_FLAG,
             Self::Trans => TRANS_FLAG,
+            Self::Bisexual => BISEXUAL_FLAG,
+            Self::Pansexual => PANSEXUAL_FLAG,
+            Self::Nonbinary => NONBINARY_FLAG,
+            Self::Lesbian => LESBIAN_FLAG,
+            Self::Progress => PROGRESS_FLAG,
         }
     }
 }
@@ -144,128 +515,1004 @@ enum Coloring {
     Colorous,
     SolidPride,
     GradientPride,
+    WidthGradient,
+    ByRadius,
+    CustomGradient,
+}
+
+/// Color mode for all of the colorings that stroke segments as lines.
+///
+/// `Coloring::WidthGradient` is drawn as a mesh instead, so it falls back to the solid `fg`.
+/// `origin` and `radius` are the starting point and extent of the curve as it's drawn, and `custom`
+/// holds the stops of `Coloring::CustomGradient`.
+#[allow(clippy::too_many_arguments)]
+fn color_mode<'a>(
+    coloring: Coloring,
+    gradient: GradientKind,
+    pride_flag: PrideFlag,
+    custom: &'a [Band],
+    phase: f64,
+    fg: egui::Color32,
+    origin: egui::Pos2,
+    radius: f32,
+) -> Box<dyn ColorMode + 'a> {
+    match coloring {
+        Coloring::None | Coloring::WidthGradient => Box::new(fg),
+        Coloring::Colorous => Box::new(GradientStroke {
+            grad: Some(gradient.into_colorous()),
+            phase,
+        }),
+        Coloring::SolidPride => Box::new(SolidBands {
+            colors: pride_flag.into_bands(),
+        }),
+        Coloring::GradientPride => Box::new(GradientBands {
+            colors: pride_flag.into_bands(),
+        }),
+        Coloring::ByRadius => Box::new(RadiusStroke {
+            origin,
+            max: radius,
+            grad: Some(gradient.into_colorous()),
+            phase,
+        }),
+        Coloring::CustomGradient => Box::new(GradientBands { colors: custom }),
+    }
+}
+
+/// Color mode that packs positions along the gradient into the colors, for the colorings that
+/// can be shifted by a phase. The others don't move with the phase, so they have `None`.
+fn position_mode(
+    coloring: Coloring,
+    origin: egui::Pos2,
+    radius: f32,
+) -> Option<Box<dyn ColorMode + 'static>> {
+    match coloring {
+        Coloring::Colorous => Some(Box::new(GradientStroke {
+            grad: None,
+            phase: 0.0,
+        })),
+        Coloring::ByRadius => Some(Box::new(RadiusStroke {
+            origin,
+            max: radius,
+            grad: None,
+            phase: 0.0,
+        })),
+        _ => None,
+    }
 }
 
-const RAINBOW_FLAG: &[egui::Color32] = &[
-    egui::Color32::from_rgb(0xe5, 0x00, 0x00),
-    egui::Color32::from_rgb(0xfe, 0x8d, 0x00),
-    egui::Color32::from_rgb(0xff, 0xee, 0x00),
-    egui::Color32::from_rgb(0x02, 0x81, 0x21),
-    egui::Color32::from_rgb(0x00, 0x4c, 0xff),
-    egui::Color32::from_rgb(0x76, 0x00, 0x88),
+/// Settings captured by the "Capture presets" command
+#[cfg(not(target_arch = "wasm32"))]
+struct Preset {
+    flags: CurveFlags,
+    depth: u8,
+    coloring: Coloring,
+    gradient: GradientKind,
+    pride_flag: PrideFlag,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl Preset {
+    /// Descriptive file name for an image of this preset
+    fn file_name(&self) -> String {
+        let kind = if self.flags.contains(CurveFlags::LEVY) {
+            "levy"
+        } else {
+            "dragon"
+        };
+        let flip = if self.flags.contains(CurveFlags::FLIP) {
+            "_flip"
+        } else {
+            ""
+        };
+        let coloring = match self.coloring {
+            Coloring::Colorous | Coloring::ByRadius => {
+                format!("{:?}_{:?}", self.coloring, self.gradient)
+            }
+            Coloring::SolidPride | Coloring::GradientPride => {
+                format!("{:?}_{:?}", self.coloring, self.pride_flag)
+            }
+            _ => format!("{:?}", self.coloring),
+        };
+        format!(
+            "{kind}{flip}_d{}_{}.png",
+            self.depth,
+            coloring.to_lowercase()
+        )
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const PRESETS: &[Preset] = &[
+    Preset {
+        flags: CurveFlags::DRAGON,
+        depth: 12,
+        coloring: Coloring::None,
+        gradient: GradientKind::Viridis,
+        pride_flag: PrideFlag::Rainbow,
+    },
+    Preset {
+        flags: CurveFlags::DRAGON,
+        depth: 14,
+        coloring: Coloring::Colorous,
+        gradient: GradientKind::Viridis,
+        pride_flag: PrideFlag::Rainbow,
+    },
+    Preset {
+        flags: CurveFlags::DRAGON,
+        depth: 16,
+        coloring: Coloring::Colorous,
+        gradient: GradientKind::Sinebow,
+        pride_flag: PrideFlag::Rainbow,
+    },
+    Preset {
+        flags: CurveFlags::FLIP,
+        depth: 13,
+        coloring: Coloring::ByRadius,
+        gradient: GradientKind::Plasma,
+        pride_flag: PrideFlag::Rainbow,
+    },
+    Preset {
+        flags: CurveFlags::LEVY,
+        depth: 12,
+        coloring: Coloring::GradientPride,
+        gradient: GradientKind::Viridis,
+        pride_flag: PrideFlag::Rainbow,
+    },
+    Preset {
+        flags: CurveFlags::LEVY,
+        depth: 14,
+        coloring: Coloring::SolidPride,
+        gradient: GradientKind::Viridis,
+        pride_flag: PrideFlag::Trans,
+    },
 ];
-const TRANS_FLAG: &[egui::Color32] = &[
-    egui::Color32::from_rgb(0x5b, 0xcf, 0xfb),
-    egui::Color32::from_rgb(0xf5, 0xab, 0xb9),
-    egui::Color32::from_rgb(0xff, 0xff, 0xff),
-    egui::Color32::from_rgb(0xf5, 0xab, 0xb9),
-    egui::Color32::from_rgb(0x5b, 0xcf, 0xfb),
+
+/// Size in pixels of the images written by the "Capture presets" command
+#[cfg(not(target_arch = "wasm32"))]
+const PRESET_SIZE: u32 = 1024;
+
+/// Render every entry of [`PRESETS`] into `dir`, returning how many files were written
+#[cfg(not(target_arch = "wasm32"))]
+fn capture_presets(
+    dir: &std::path::Path,
+    background: egui::Color32,
+    fg: egui::Color32,
+    width: f32,
+) -> image::ImageResult<usize> {
+    std::fs::create_dir_all(dir)?;
+    for preset in PRESETS {
+        let curve = DragonCurve::builder()
+            .flags(preset.flags)
+            .depth(preset.depth)
+            .build();
+        let style = raster::RasterStyle {
+            render: RenderStyle::Diagonal45,
+            corner_radius: 0.0,
+            rotation: 0.0,
+            width,
+            background,
+        };
+        let transform = raster::transform(&curve, PRESET_SIZE, style);
+        let mut mode = color_mode(
+            preset.coloring,
+            preset.gradient,
+            preset.pride_flag,
+            &[],
+            0.0,
+            fg,
+            transform.origin.into(),
+            curve.radius(transform.scale),
+        );
+        raster::render(&curve, PRESET_SIZE, style, &mut *mode)
+            .save(dir.join(preset.file_name()))?;
+    }
+    Ok(PRESETS.len())
+}
+
+/// Run [`capture_presets`] into `presets`, drawing in the foreground of `visuals` and
+/// `line_width` if one is set, and describe how it went
+#[cfg(not(target_arch = "wasm32"))]
+fn capture_status(
+    background: egui::Color32,
+    visuals: &egui::Visuals,
+    line_width: Option<f32>,
+) -> String {
+    let dir = std::path::Path::new("presets");
+    let stroke = visuals.widgets.active.fg_stroke;
+    match capture_presets(
+        dir,
+        background,
+        stroke.color,
+        line_width.unwrap_or(stroke.width),
+    ) {
+        Ok(n) => format!("Wrote {n} files to {}", dir.display()),
+        Err(err) => format!("Failed to capture presets: {err}"),
+    }
+}
+
+/// Ask where to save the SVG document `svg`, suggesting `name`, and write it there.
+///
+/// Returns a status message, or `None` if the dialog was cancelled.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_svg(name: &str, svg: &str) -> Option<String> {
+    let path = rfd::FileDialog::new()
+        .add_filter("SVG", &["svg"])
+        .set_file_name(name)
+        .save_file()?;
+    Some(match std::fs::write(&path, svg) {
+        Ok(()) => format!("Wrote {}", path.display()),
+        Err(err) => format!("Failed to save {}: {err}", path.display()),
+    })
+}
+
+/// Have the browser download the SVG document `svg` as `name`.
+///
+/// Returns a status message, which is always `Some` since there's no dialog to cancel.
+#[cfg(target_arch = "wasm32")]
+fn save_svg(name: &str, svg: &str) -> Option<String> {
+    use wasm_bindgen::JsCast;
+
+    let download = || -> Result<(), wasm_bindgen::JsValue> {
+        let parts = js_sys::Array::of1(&svg.into());
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("image/svg+xml");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+        let document = web_sys::window()
+            .and_then(|w| w.document())
+            .ok_or("no document")?;
+        let link = document
+            .create_element("a")?
+            .dyn_into::<web_sys::HtmlAnchorElement>()?;
+        link.set_href(&url);
+        link.set_download(name);
+        link.click();
+        web_sys::Url::revoke_object_url(&url)
+    };
+    Some(match download() {
+        Ok(()) => format!("Downloaded {name}"),
+        Err(err) => format!("Failed to download {name}: {err:?}"),
+    })
+}
+
+/// Angle to frame `curve` at when it's turned by `rotation` and drawn in `style`.
+///
+/// Drawing diagonals at right angles turns the whole curve, so this turns the framing along with
+/// it to keep the curve centered.
+fn frame_angle(curve: &DragonCurve, style: RenderStyle, rotation: f32) -> f32 {
+    let twisted =
+        style == RenderStyle::Sharp90 && curve.segments().next().is_some_and(|d| d.is_diagonal());
+    if twisted {
+        rotation + std::f32::consts::FRAC_PI_4
+    } else {
+        rotation
+    }
+}
+
+/// Draw the first `count` segments of `curve` in `style` with any renderer that collects them
+fn draw_segments(
+    curve: &DragonCurve,
+    step: f32,
+    style: RenderStyle,
+    count: usize,
+    out: &mut dyn DrawSink,
+) {
+    for seg in curve.segments_styled(style).take(count) {
+        seg.draw(out, step);
+    }
+}
+
+/// Index and screen endpoints of the segment among the first `count` that's closest to `target`, if
+/// any is within `reach` of it, with the curve drawn from `pos` the same way as on screen
+#[allow(clippy::too_many_arguments)]
+fn nearest_segment(
+    curve: &DragonCurve,
+    step: f32,
+    style: RenderStyle,
+    count: usize,
+    pos: egui::Pos2,
+    rot: egui::emath::Rot2,
+    target: egui::Pos2,
+    reach: f32,
+) -> Option<(usize, [egui::Pos2; 2])> {
+    let mut best = None;
+    let mut best_dist = reach;
+    let mut a = pos;
+    for (i, dir) in curve.segments_styled(style).take(count).enumerate() {
+        let b = a + rot * egui::Vec2::from(dir.as_vec()) * step;
+        let seg = b - a;
+        let t = ((target - a).dot(seg) / seg.length_sq()).clamp(0.0, 1.0);
+        let dist = target.distance(a + seg * t);
+        // ties go to the later segment, which is drawn on top
+        if dist <= best_dist {
+            best_dist = dist;
+            best = Some((i, [a, b]));
+        }
+        a = b;
+    }
+    best
+}
+
+/// Draw gridlines `spacing` apart across `rect`, aligned with `origin` and turned by `rot`, with
+/// the axes through `origin` in `axis_color`
+fn draw_grid(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    origin: egui::Pos2,
+    rot: egui::emath::Rot2,
+    spacing: f32,
+    color: egui::Color32,
+    axis_color: egui::Color32,
+) {
+    let inv = rot.inverse();
+    let corners = [
+        rect.left_top(),
+        rect.right_top(),
+        rect.left_bottom(),
+        rect.right_bottom(),
+    ]
+    .map(|c| inv * (c - origin));
+    let min = corners.iter().fold(egui::Vec2::INFINITY, |a, &c| a.min(c));
+    let max = corners.iter().fold(-egui::Vec2::INFINITY, |a, &c| a.max(c));
+    let stroke = |i: i32| (1.0, if i == 0 { axis_color } else { color });
+    for i in (min.x / spacing).ceil() as i32..=(max.x / spacing).floor() as i32 {
+        let x = i as f32 * spacing;
+        painter.line_segment(
+            [
+                origin + rot * egui::vec2(x, min.y),
+                origin + rot * egui::vec2(x, max.y),
+            ],
+            stroke(i),
+        );
+    }
+    for j in (min.y / spacing).ceil() as i32..=(max.y / spacing).floor() as i32 {
+        let y = j as f32 * spacing;
+        painter.line_segment(
+            [
+                origin + rot * egui::vec2(min.x, y),
+                origin + rot * egui::vec2(max.x, y),
+            ],
+            stroke(j),
+        );
+    }
+}
+
+/// Most segments the GUI and CLI will build, unless overridden by `DRAGONS_MAX_SEGMENTS`
+const DEFAULT_SEGMENT_BUDGET: usize = 1 << 16;
+/// Depth the attractor is drawn at, whatever the segment budget
+const ATTRACTOR_DEPTH: u8 = 16;
+/// Colors of the dots marking the start and end of the curve, the same as in SVG output
+const START_MARKER: egui::Color32 = egui::Color32::from_rgb(0x22, 0xaa, 0x22);
+const END_MARKER: egui::Color32 = egui::Color32::from_rgb(0xdd, 0x22, 0x22);
+/// View size at which world-space stroke widths match screen pixels
+const REFERENCE_SIZE: f32 = 512.0;
+
+const RAINBOW_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xe5, 0x00, 0x00), 1.0),
+    (egui::Color32::from_rgb(0xfe, 0x8d, 0x00), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xee, 0x00), 1.0),
+    (egui::Color32::from_rgb(0x02, 0x81, 0x21), 1.0),
+    (egui::Color32::from_rgb(0x00, 0x4c, 0xff), 1.0),
+    (egui::Color32::from_rgb(0x76, 0x00, 0x88), 1.0),
+];
+const TRANS_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0x5b, 0xcf, 0xfb), 1.0),
+    (egui::Color32::from_rgb(0xf5, 0xab, 0xb9), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xff, 0xff), 1.0),
+    (egui::Color32::from_rgb(0xf5, 0xab, 0xb9), 1.0),
+    (egui::Color32::from_rgb(0x5b, 0xcf, 0xfb), 1.0),
+];
+const BISEXUAL_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xd6, 0x02, 0x70), 2.0),
+    (egui::Color32::from_rgb(0x9b, 0x4f, 0x96), 1.0),
+    (egui::Color32::from_rgb(0x00, 0x38, 0xa8), 2.0),
+];
+const PANSEXUAL_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xff, 0x21, 0x8c), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xd8, 0x00), 1.0),
+    (egui::Color32::from_rgb(0x21, 0xb1, 0xff), 1.0),
+];
+const NONBINARY_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xfc, 0xf4, 0x34), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xff, 0xff), 1.0),
+    (egui::Color32::from_rgb(0x9c, 0x59, 0xd1), 1.0),
+    (egui::Color32::from_rgb(0x2c, 0x2c, 0x2c), 1.0),
+];
+/// The five-stripe version
+const LESBIAN_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xd6, 0x29, 0x00), 1.0),
+    (egui::Color32::from_rgb(0xff, 0x9b, 0x55), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xff, 0xff), 1.0),
+    (egui::Color32::from_rgb(0xd4, 0x62, 0xa5), 1.0),
+    (egui::Color32::from_rgb(0xa5, 0x00, 0x62), 1.0),
+];
+/// The chevron is flattened into bands leading into the rainbow, from the innermost outwards
+const PROGRESS_FLAG: &[Band] = &[
+    (egui::Color32::from_rgb(0xff, 0xff, 0xff), 1.0),
+    (egui::Color32::from_rgb(0xf5, 0xa9, 0xb8), 1.0),
+    (egui::Color32::from_rgb(0x5b, 0xce, 0xfa), 1.0),
+    (egui::Color32::from_rgb(0x78, 0x4f, 0x17), 1.0),
+    (egui::Color32::from_rgb(0x00, 0x00, 0x00), 1.0),
+    (egui::Color32::from_rgb(0xe4, 0x03, 0x03), 1.0),
+    (egui::Color32::from_rgb(0xff, 0x8c, 0x00), 1.0),
+    (egui::Color32::from_rgb(0xff, 0xed, 0x00), 1.0),
+    (egui::Color32::from_rgb(0x00, 0x80, 0x26), 1.0),
+    (egui::Color32::from_rgb(0x00, 0x4d, 0xff), 1.0),
+    (egui::Color32::from_rgb(0x75, 0x07, 0x87), 1.0),
 ];
 
+/// Most segments to build, from the `DRAGONS_MAX_SEGMENTS` environment variable if it's set
+fn segment_budget() -> usize {
+    std::env::var("DRAGONS_MAX_SEGMENTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SEGMENT_BUDGET)
+}
+
 fn main() {
-    let start = dragon::Dir::Np0;
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let args = std::env::args().skip(1).collect::<Vec<_>>();
+        if !args.is_empty() {
+            std::process::exit(cli::run(&args));
+        }
+    }
     let mut show = true;
     let mut flip = false;
     let mut levy = false;
     let mut depth = 0;
-    let mut curve = DragonCurve::new(start, CurveFlags::DRAGON);
+    let max_depth = dragon::max_depth(segment_budget());
+    let mut curve = DragonCurve::default();
     let mut coloring = Coloring::None;
     let mut gradient = GradientKind::Viridis;
     let mut pride_flag = PrideFlag::Rainbow;
-    let res = eframe::run_simple_native("Dragon", Default::default(), move |ctx, _| {
-        if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+    let mut stats = None::<((u8, CurveFlags, Dir), dragon::CurveInfo)>;
+    let mut pan = egui::Vec2::ZERO;
+    let mut zoom = 1.0f32;
+    let mut fill_enabled = false;
+    let mut fill_color = egui::Color32::from_rgb(0xa0, 0xc0, 0xff);
+    let mut fill_rule = FillRule::NonZero;
+    // along with the rotation it was tessellated at
+    let mut cached_mesh = None::<(CurveKey, f32, egui::Mesh)>;
+    // every stop of a custom gradient is spaced evenly, so the weights all stay at 1
+    let mut custom_stops: Vec<Band> = vec![
+        (egui::Color32::from_rgb(0x10, 0x20, 0x60), 1.0),
+        (egui::Color32::from_rgb(0xa0, 0xc0, 0xff), 1.0),
+    ];
+    let mut edge_color = egui::Color32::from_rgb(0x10, 0x20, 0x60);
+    let mut center_color = egui::Color32::from_rgb(0xa0, 0xc0, 0xff);
+    let mut tube_width = 4.0;
+    let mut screensaver = false;
+    let mut saver_start = 0.0;
+    let mut show_attractor = false;
+    let mut show_grid = false;
+    let mut attractor = None::<(AttractorKey, DragonCurve, egui::Mesh)>;
+    let mut show_indices = false;
+    let mut label_every = 16;
+    let mut show_highlight = false;
+    let mut hover_highlight = true;
+    let mut show_markers = false;
+    let mut marker_radius = 4.0f32;
+    let mut highlight_index = 0usize;
+    let mut highlight_count = 1usize;
+    let mut constant_width = true;
+    let mut show_fps = false;
+    let mut avg_dt = 0.0f32;
+    let mut render_style = RenderStyle::Diagonal45;
+    let mut corner_radius = 0.0f32;
+    let mut export_status = None::<String>;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut png_scale = 1u32;
+    // `None` follows the theme's panel color
+    let mut custom_background = None::<egui::Color32>;
+    let mut transparent = false;
+    // `None` follows the theme's stroke width
+    let mut line_width = None::<f32>;
+    let mut stroke_dash = StrokeDash::Solid;
+    // size of the view and line width as of the last frame, so exports can match it
+    let mut view_size = REFERENCE_SIZE;
+    let mut view_width = 1.0f32;
+    let mut animate = false;
+    let mut anim_speed = 200.0f32;
+    let mut anim_progress = 0.0f32;
+    // in degrees, clockwise, on top of whatever the screensaver adds
+    let mut view_rotation = 0.0f32;
+    let update = move |ctx: &egui::Context, _: &mut eframe::Frame| {
+        let mut rotation = view_rotation.to_radians();
+        let mut phase = 0.0;
+        let mut drawn = 0;
+        if screensaver {
+            let elapsed = ctx.input(|i| i.time) - saver_start;
+            // ignore input for a moment so the click that started it doesn't end it
+            let woke = elapsed > 1.0
+                && ctx.input(|i| {
+                    i.events.iter().any(|e| {
+                        matches!(
+                            e,
+                            egui::Event::Key { .. }
+                                | egui::Event::PointerMoved(_)
+                                | egui::Event::PointerButton { .. }
+                                | egui::Event::MouseWheel { .. }
+                        )
+                    })
+                });
+            if woke {
+                screensaver = false;
+                show = true;
+            } else {
+                rotation += (elapsed * 0.2) as f32;
+                phase = (elapsed * 0.05).fract();
+                ctx.request_repaint();
+            }
+        } else if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
             show = !show;
         }
-        egui::CentralPanel::default().show(ctx, |ui| {
+        // auto-repeat would run through the levels faster than they can be watched, and a focused
+        // slider already handles the arrow keys itself
+        let depth_step = ctx.input(|i| {
+            i.events
+                .iter()
+                .map(|e| match e {
+                    egui::Event::Key {
+                        key: egui::Key::ArrowUp,
+                        pressed: true,
+                        repeat: false,
+                        ..
+                    } => 1,
+                    egui::Event::Key {
+                        key: egui::Key::ArrowDown,
+                        pressed: true,
+                        repeat: false,
+                        ..
+                    } => -1,
+                    _ => 0,
+                })
+                .sum::<i32>()
+        });
+        if depth_step != 0 && ctx.memory(|m| m.focused().is_none()) {
+            depth = (depth as i32 + depth_step).clamp(0, max_depth as i32) as u8;
+            curve.set_depth(depth);
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::R)) {
+            pan = egui::Vec2::ZERO;
+            zoom = 1.0;
+        }
+        let shown = if animate {
+            anim_progress += ctx.input(|i| i.stable_dt) * anim_speed;
+            // start over once the whole curve is on screen
+            if anim_progress > curve.len() as f32 {
+                anim_progress = 0.0;
+            }
+            ctx.request_repaint();
+            anim_progress as usize
+        } else {
+            curve.len()
+        };
+        let background = if transparent {
+            egui::Color32::TRANSPARENT
+        } else {
+            custom_background.unwrap_or(ctx.style().visuals.panel_fill)
+        };
+        // handled whether or not the options are showing, since a bare view is what's worth capturing
+        #[cfg(not(target_arch = "wasm32"))]
+        if ctx.input(|i| i.key_pressed(egui::Key::P)) {
+            export_status = Some(capture_status(background, &ctx.style().visuals, line_width));
+        }
+        let panel = egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&ctx.style()).fill(background));
+        panel.show(ctx, |ui| {
             ui.centered_and_justified(|ui| {
                 let rect = ui.max_rect();
-                let size = rect.size().min_elem();
+                let fit = rect.size().min_elem();
                 let offset = match rect.aspect_ratio().partial_cmp(&1.0) {
-                    Some(Ordering::Less) => egui::vec2(0.0, (rect.height() - size) * 0.5),
-                    Some(Ordering::Greater) => egui::vec2((rect.width() - size) * 0.5, 0.0),
+                    Some(Ordering::Less) => egui::vec2(0.0, (rect.height() - fit) * 0.5),
+                    Some(Ordering::Greater) => egui::vec2((rect.width() - fit) * 0.5, 0.0),
                     _ => egui::Vec2::ZERO,
                 };
-                let mut step = size / (1 << (depth / 2) + 1) as f32;
-                if depth & 1 != 0 {
-                    step *= std::f32::consts::FRAC_1_SQRT_2;
-                }
-                let pos = rect.min + offset + egui::vec2(size * 0.25, size * 0.5);
-                match coloring {
-                    Coloring::None => {
-                        let mut draw = EguiDraw {
-                            painter: ui.painter(),
-                            pos,
-                            stroke: ui.style().visuals.widgets.active.fg_stroke,
-                        };
-                        for seg in curve.list() {
-                            seg.draw(&mut draw, step);
+                let fit_center = rect.min + offset + egui::Vec2::splat(fit * 0.5);
+                let response = ui.interact(rect, ui.id().with("view"), egui::Sense::drag());
+                pan += response.drag_delta();
+                if response.hovered() {
+                    let (scroll, pinch, pointer) = ui.input(|i| {
+                        (
+                            i.smooth_scroll_delta.y,
+                            i.zoom_delta(),
+                            i.pointer.hover_pos(),
+                        )
+                    });
+                    let factor = pinch * (scroll / 200.0).exp();
+                    if factor != 1.0 {
+                        let new_zoom = (zoom * factor).clamp(0.1, 1000.0);
+                        // keep whatever's under the pointer in place
+                        if let Some(p) = pointer {
+                            pan += (p - (fit_center + pan)) * (1.0 - new_zoom / zoom);
                         }
+                        zoom = new_zoom;
+                    }
+                }
+                let size = fit * zoom;
+                let rot = egui::emath::Rot2::from_angle(rotation);
+                let frame_angle = frame_angle(&curve, render_style, rotation);
+                let frame = egui::emath::Rot2::from_angle(frame_angle);
+                let center = fit_center + pan;
+                let framing = Transform::framed(&curve, center.into(), size, frame_angle);
+                let step = framing.scale;
+                let pos = egui::Pos2::from(framing.origin);
+                let base_width =
+                    line_width.unwrap_or(ui.style().visuals.widgets.active.fg_stroke.width);
+                // the curve is fit to the panel, so resizing it zooms the view
+                let scaled = |size: f32| {
+                    if constant_width {
+                        base_width
+                    } else {
+                        base_width * size / REFERENCE_SIZE
                     }
-                    Coloring::Colorous => {
-                        let mut draw = EguiDraw {
-                            painter: ui.painter(),
+                };
+                let width = scaled(size);
+                // exports are framed at `fit`, whatever the zoom
+                view_size = fit;
+                view_width = scaled(fit);
+                if show_grid {
+                    let spacing = curve.grid_spacing(step, render_style);
+                    // lines only a few pixels apart just turn into a flat wash
+                    if spacing >= 4.0 {
+                        let faint = ui.visuals().weak_text_color();
+                        draw_grid(
+                            ui.painter(),
+                            rect,
                             pos,
-                            stroke: GradientStroke {
-                                width: ui.style().visuals.widgets.active.fg_stroke.width,
-                                count: 0,
-                                max: curve.len(),
-                                grad: gradient.into_colorous(),
-                            },
+                            rot,
+                            spacing,
+                            faint.gamma_multiply(0.15),
+                            faint.gamma_multiply(0.5),
+                        );
+                    }
+                }
+                if show_attractor {
+                    let faint = ui.visuals().weak_text_color().gamma_multiply(0.4);
+                    let key = AttractorKey {
+                        flags: curve.flags(),
+                        start: curve.start(),
+                        pos,
+                        size,
+                        rot: frame,
+                        width,
+                        render_style,
+                        color: faint,
+                        pixels_per_point: ctx.pixels_per_point(),
+                    };
+                    if attractor.as_ref().is_none_or(|(k, _, _)| *k != key) {
+                        let a = match attractor.take() {
+                            Some((k, a, _)) if k.flags == key.flags && k.start == key.start => a,
+                            _ => DragonCurve::builder()
+                                .start(curve.start())
+                                .flags(curve.flags())
+                                .depth(ATTRACTOR_DEPTH)
+                                .build(),
                         };
-                        for seg in curve.list() {
-                            seg.draw(&mut draw, step);
+                        // every depth shares the same endpoints, so this lines up with the curve
+                        let mut color = faint;
+                        let mut shapes = Vec::new();
+                        EguiDraw {
+                            shapes: &mut shapes,
+                            pos,
+                            rot: frame,
+                            width,
+                            mode: &mut color,
+                            index: 0,
+                            total: 0,
+                            dir: Dir::Np0,
+                            run: Vec::new(),
+                            run_color: egui::Color32::TRANSPARENT,
+                            dash: None,
+                            dash_phase: 0.0,
                         }
+                        .draw_curve(
+                            &a,
+                            a.step(size),
+                            render_style,
+                            0.0,
+                            a.len(),
+                        );
+                        let mut tess = egui::epaint::Tessellator::new(
+                            ctx.pixels_per_point(),
+                            ctx.tessellation_options(|o| *o),
+                            ctx.fonts(|f| f.font_image_size()),
+                            Vec::new(),
+                        );
+                        let mut mesh = egui::Mesh::default();
+                        tess.tessellate_shape(egui::Shape::Vec(shapes), &mut mesh);
+                        attractor = Some((key, a, mesh));
                     }
-                    Coloring::SolidPride => {
-                        let mut draw = EguiDraw {
-                            painter: ui.painter(),
-                            pos,
-                            stroke: SolidBands {
-                                width: ui.style().visuals.widgets.active.fg_stroke.width,
-                                count: 0,
-                                max: curve.len(),
-                                colors: pride_flag.into_bands(),
-                            },
-                        };
-                        for seg in curve.list() {
-                            seg.draw(&mut draw, step);
+                    let (_, a, mesh) = attractor.as_ref().unwrap();
+                    ui.painter().add(mesh.clone());
+                    drawn += a.len();
+                }
+                let fg = ui.style().visuals.widgets.active.fg_stroke.color;
+                let fill = fill_enabled.then_some((fill_color, fill_rule));
+                if coloring == Coloring::WidthGradient && !fill_enabled {
+                    let mut draw = MeshDraw {
+                        mesh: egui::Mesh::default(),
+                        pos,
+                        rot,
+                        width: tube_width,
+                        edge: edge_color,
+                        center: center_color,
+                    };
+                    draw_segments(&curve, step, render_style, shown, &mut draw);
+                    ui.painter().add(draw.mesh);
+                } else {
+                    let positions = phase != 0.0
+                        && fill.is_none()
+                        && matches!(coloring, Coloring::Colorous | Coloring::ByRadius);
+                    // anything within reach of the center can be turned into view
+                    let reach = [
+                        rect.left_top(),
+                        rect.right_top(),
+                        rect.left_bottom(),
+                        rect.right_bottom(),
+                    ]
+                    .map(|corner| corner.distance(center))
+                    .into_iter()
+                    .fold(0.0, f32::max);
+                    let key = CurveKey {
+                        depth: curve.depth(),
+                        flags: curve.flags(),
+                        start: curve.start(),
+                        coloring,
+                        gradient,
+                        pride_flag,
+                        custom_stops: custom_stops.clone(),
+                        positions,
+                        fg,
+                        width,
+                        center,
+                        step,
+                        cull: egui::Rect::from_center_size(center, egui::Vec2::splat(reach * 2.0)),
+                        render_style,
+                        corner_radius,
+                        shown,
+                        stroke_dash,
+                        fill,
+                        pixels_per_point: ctx.pixels_per_point(),
+                    };
+                    if cached_mesh.as_ref().is_none_or(|(k, _, _)| *k != key) {
+                        // the radius walks the whole curve, so it's only worked out on a miss
+                        let radius = curve.radius(step);
+                        let mut mode = position_mode(coloring, pos, radius)
+                            .filter(|_| positions)
+                            .unwrap_or_else(|| {
+                                color_mode(
+                                    coloring,
+                                    gradient,
+                                    pride_flag,
+                                    &custom_stops,
+                                    phase,
+                                    fg,
+                                    pos,
+                                    radius,
+                                )
+                            });
+                        let mut mesh = egui::Mesh::default();
+                        if let Some((color, rule)) = fill {
+                            for corners in curve.fill_trapezoids(step, render_style, rule) {
+                                let idx = mesh.vertices.len() as u32;
+                                for (x, y) in corners {
+                                    mesh.colored_vertex(pos + rot * egui::vec2(x, y), color);
+                                }
+                                mesh.add_triangle(idx, idx + 1, idx + 2);
+                                mesh.add_triangle(idx, idx + 2, idx + 3);
+                            }
+                        } else {
+                            let mut shapes = Vec::new();
+                            EguiDraw {
+                                shapes: &mut shapes,
+                                pos,
+                                rot,
+                                width,
+                                mode: &mut *mode,
+                                index: 0,
+                                total: 0,
+                                dir: Dir::Np0,
+                                run: Vec::new(),
+                                run_color: egui::Color32::TRANSPARENT,
+                                dash: stroke_dash.pattern(width),
+                                dash_phase: 0.0,
+                            }
+                            .draw_curve(
+                                &curve,
+                                step,
+                                render_style,
+                                corner_radius * step,
+                                shown,
+                            );
+                            let mut tess = egui::epaint::Tessellator::new(
+                                ctx.pixels_per_point(),
+                                ctx.tessellation_options(|o| *o),
+                                ctx.fonts(|f| f.font_image_size()),
+                                Vec::new(),
+                            );
+                            // zoomed in, most of the curve is off screen and can be skipped
+                            tess.set_clip_rect(key.cull);
+                            tess.tessellate_shape(egui::Shape::Vec(shapes), &mut mesh);
                         }
+                        cached_mesh = Some((key, rotation, mesh));
                     }
-                    Coloring::GradientPride => {
-                        let mut draw = EguiDraw {
-                            painter: ui.painter(),
-                            pos,
-                            stroke: GradientBands {
-                                width: ui.style().visuals.widgets.active.fg_stroke.width,
-                                count: 0,
-                                max: curve.len(),
-                                colors: pride_flag.into_bands(),
-                            },
-                        };
-                        for seg in curve.list() {
-                            seg.draw(&mut draw, step);
+                    // `Shape::Mesh` owns its mesh in egui 0.30, so there's no handle to share
+                    if let Some((key, built, mesh)) = &cached_mesh {
+                        let mut mesh = mesh.clone();
+                        if rotation != *built {
+                            let turn = egui::emath::Rot2::from_angle(rotation - built);
+                            for v in &mut mesh.vertices {
+                                v.pos = center + turn * (v.pos - center);
+                            }
+                        }
+                        if key.positions {
+                            let grad = gradient.into_colorous();
+                            for v in &mut mesh.vertices {
+                                v.color = paint_position(v.color, grad, phase);
+                            }
                         }
+                        ui.painter().add(mesh);
+                    }
+                }
+                drawn += shown;
+                if show_highlight {
+                    let mut color = ui.visuals().warn_fg_color;
+                    let mut shapes = Vec::new();
+                    let mut draw = EguiDraw {
+                        shapes: &mut shapes,
+                        pos,
+                        rot,
+                        width: width * 3.0 + 2.0,
+                        mode: &mut color,
+                        index: highlight_index,
+                        total: curve.len(),
+                        dir: Dir::Np0,
+                        run: Vec::new(),
+                        run_color: egui::Color32::TRANSPARENT,
+                        dash: None,
+                        dash_phase: 0.0,
+                    };
+                    let range = highlight_index..highlight_index + highlight_count;
+                    curve.draw_range_styled(&mut draw, step, render_style, range);
+                    draw.flush_run();
+                    ui.painter().extend(shapes);
+                }
+                if show_markers {
+                    let end = curve
+                        .segments_styled(render_style)
+                        .take(shown)
+                        .fold(pos, |p, d| p + rot * egui::Vec2::from(d.as_vec()) * step);
+                    let painter = ui.painter();
+                    painter.circle_filled(pos, marker_radius, START_MARKER);
+                    painter.circle_filled(end, marker_radius, END_MARKER);
+                }
+                let hover = ui.input(|i| i.pointer.hover_pos());
+                if let Some(target) = hover.filter(|_| hover_highlight && response.hovered()) {
+                    let reach = (width * 2.0).max(6.0);
+                    let near =
+                        nearest_segment(&curve, step, render_style, shown, pos, rot, target, reach);
+                    if let Some((i, ends)) = near {
+                        ui.painter().line_segment(
+                            ends,
+                            (width * 3.0 + 2.0, ui.visuals().selection.bg_fill),
+                        );
+                        response.on_hover_text_at_pointer(format!("Segment {i}"));
                     }
                 }
+                if show_indices {
+                    let font = egui::FontId::proportional((step * 0.5).clamp(8.0, 18.0));
+                    let mut draw = LabelDraw {
+                        painter: ui.painter(),
+                        pos,
+                        rot,
+                        index: 0,
+                        every: label_every,
+                        font,
+                        color: ui.visuals().strong_text_color(),
+                        taken: HashSet::new(),
+                    };
+                    draw_segments(&curve, step, render_style, shown, &mut draw);
+                }
             });
         });
+        if show_fps {
+            // exponential moving average over roughly the last 20 frames
+            let dt = ctx.input(|i| i.unstable_dt);
+            avg_dt = if avg_dt > 0.0 {
+                avg_dt * 0.95 + dt * 0.05
+            } else {
+                dt
+            };
+            egui::Area::new(egui::Id::new("fps"))
+                .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+                .interactable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "{:.0} FPS\n{:.2} ms\n{drawn} segments/frame",
+                        1.0 / avg_dt,
+                        avg_dt * 1000.0
+                    ));
+                });
+            ctx.request_repaint();
+        }
         if show {
             egui::Window::new("Curve Options").show(ctx, |ui| {
                 let mut changed = false;
-                changed |= ui.checkbox(&mut flip, "Flip").changed();
+                changed |= ui
+                    .checkbox(&mut flip, "Flip")
+                    .on_hover_text("Mirror the curve across the line between its endpoints")
+                    .changed();
                 changed |= ui.checkbox(&mut levy, "Levy").changed();
-                ui.add(egui::Slider::new(&mut depth, 0..=16)).changed();
-                if changed {
-                    let mut flags = CurveFlags::NONE;
-                    if flip {
-                        flags |= CurveFlags::FLIP;
+                let mut start = curve.start();
+                egui::ComboBox::new("Start", "Start")
+                    .selected_text(start.to_string())
+                    .show_ui(ui, |ui| {
+                        for dir in Dir::ALL {
+                            ui.selectable_value(&mut start, dir, dir.to_string());
+                        }
+                    })
+                    .response
+                    .on_hover_text("Direction of the first segment at depth 0, which turns the whole curve");
+                if start != curve.start() {
+                    curve.set_start(start);
+                }
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut depth, 0..=max_depth))
+                        .on_hover_text("The Up and Down arrow keys also step the depth");
+                    if changed {
+                        let mut flags = CurveFlags::NONE;
+                        if flip {
+                            flags |= CurveFlags::FLIP;
+                        }
+                        if levy {
+                            flags |= CurveFlags::LEVY;
+                        }
+                        curve.set_flags(flags);
                     }
-                    if levy {
-                        flags |= CurveFlags::LEVY;
+                    curve.set_depth(depth);
+                    let count = egui::RichText::new(format!("{} segments", curve.len()));
+                    if depth >= max_depth {
+                        ui.label(count.color(ui.visuals().warn_fg_color))
+                            .on_hover_text(format!(
+                                "Depth is capped at {max_depth}: every level doubles the segment count, so deeper curves take too much memory to build and draw. Set DRAGONS_MAX_SEGMENTS to raise the cap"
+                            ));
+                    } else {
+                        ui.label(count);
                     }
-                    curve = DragonCurve::new(start, flags);
+                    if ui.button("Reset").on_hover_text("Go back to depth 0").clicked() {
+                        curve.reset();
+                        depth = 0;
+                    }
+                });
+                let key = (curve.depth(), curve.flags(), curve.start());
+                if stats.as_ref().is_none_or(|(k, _)| *k != key) {
+                    stats = Some((key, curve.info(REFERENCE_SIZE)));
                 }
-                curve.set_depth(depth);
+                let info = &stats.as_ref().unwrap().1;
+                ui.collapsing("Statistics", |ui| {
+                    egui::Grid::new("stats").num_columns(2).show(ui, |ui| {
+                        let (min_x, min_y, max_x, max_y) = info.bounds;
+                        ui.label("Depth");
+                        ui.label(curve.depth().to_string());
+                        ui.end_row();
+                        ui.label("Segments");
+                        ui.label(info.segments.to_string());
+                        ui.end_row();
+                        ui.label("Vertices");
+                        ui.label(info.vertices.to_string());
+                        ui.end_row();
+                        ui.label("Bounds").on_hover_text(format!(
+                            "In a {REFERENCE_SIZE}-unit square, with the endpoints a quarter of the way in from each side"
+                        ));
+                        ui.label(format!(
+                            "({min_x:.1}, {min_y:.1}) to ({max_x:.1}, {max_y:.1})\n{:.1} × {:.1}",
+                            max_x - min_x,
+                            max_y - min_y
+                        ));
+                        ui.end_row();
+                    });
+                });
             });
             egui::Window::new("Display Options").show(ctx, |ui| {
                 egui::ComboBox::new("Coloring", "Coloring")
@@ -275,9 +1522,20 @@ fn main() {
                         ui.selectable_value(&mut coloring, Coloring::Colorous, "Colorous");
                         ui.selectable_value(&mut coloring, Coloring::SolidPride, "SolidPride");
                         ui.selectable_value(&mut coloring, Coloring::GradientPride, "GradientPride");
+                        ui.selectable_value(
+                            &mut coloring,
+                            Coloring::WidthGradient,
+                            "WidthGradient",
+                        );
+                        ui.selectable_value(&mut coloring, Coloring::ByRadius, "ByRadius");
+                        ui.selectable_value(
+                            &mut coloring,
+                            Coloring::CustomGradient,
+                            "CustomGradient",
+                        );
                     });
                 match coloring {
-                    Coloring::Colorous => {
+                    Coloring::Colorous | Coloring::ByRadius => {
                         egui::ComboBox::new("Gradient", "Gradient")
                             .selected_text(format!("{gradient:?}"))
                             .show_ui(ui, |ui| {
@@ -302,14 +1560,319 @@ fn main() {
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(&mut pride_flag, PrideFlag::Rainbow, "Rainbow");
                                 ui.selectable_value(&mut pride_flag, PrideFlag::Trans, "Trans");
+                                ui.selectable_value(&mut pride_flag, PrideFlag::Bisexual, "Bisexual");
+                                ui.selectable_value(&mut pride_flag, PrideFlag::Pansexual, "Pansexual");
+                                ui.selectable_value(&mut pride_flag, PrideFlag::Nonbinary, "Nonbinary");
+                                ui.selectable_value(&mut pride_flag, PrideFlag::Lesbian, "Lesbian");
+                                ui.selectable_value(&mut pride_flag, PrideFlag::Progress, "Progress");
                             });
                     }
+                    Coloring::WidthGradient => {
+                        ui.horizontal(|ui| {
+                            ui.label("Edge");
+                            ui.color_edit_button_srgba(&mut edge_color);
+                            ui.label("Center");
+                            ui.color_edit_button_srgba(&mut center_color);
+                        });
+                        ui.add(egui::Slider::new(&mut tube_width, 1.0..=32.0).text("Width"));
+                    }
+                    Coloring::CustomGradient => {
+                        ui.horizontal_wrapped(|ui| {
+                            let mut remove = None;
+                            let len = custom_stops.len();
+                            for (i, (color, _)) in custom_stops.iter_mut().enumerate() {
+                                ui.color_edit_button_srgba(color);
+                                if len > 1 && ui.small_button("-").clicked() {
+                                    remove = Some(i);
+                                }
+                            }
+                            if let Some(i) = remove {
+                                custom_stops.remove(i);
+                            }
+                            if ui.button("+").on_hover_text("Add a stop at the end").clicked() {
+                                let last = custom_stops.last().copied();
+                                custom_stops.push(last.unwrap_or((egui::Color32::WHITE, 1.0)));
+                            }
+                        });
+                    }
                     _ => {}
                 }
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut fill_enabled, "Fill")
+                        .on_hover_text("Shade the area the curve encloses, closed by a line between its endpoints, instead of stroking it");
+                    ui.add_enabled_ui(fill_enabled, |ui| {
+                        ui.color_edit_button_srgba(&mut fill_color);
+                        ui.radio_value(&mut fill_rule, FillRule::NonZero, "Non-zero");
+                        ui.radio_value(&mut fill_rule, FillRule::EvenOdd, "Even-odd");
+                    });
+                });
+                let mut sharp = render_style == RenderStyle::Sharp90;
+                if ui
+                    .checkbox(&mut sharp, "Right angles")
+                    .on_hover_text("Draw every segment horizontally or vertically")
+                    .changed()
+                {
+                    render_style = if sharp {
+                        RenderStyle::Sharp90
+                    } else {
+                        RenderStyle::Diagonal45
+                    };
+                }
+                ui.add(
+                    egui::Slider::new(&mut corner_radius, 0.0..=0.5)
+                        .text("Corner radius")
+                        .custom_formatter(|v, _| {
+                            if v == 0.0 {
+                                "Sharp".to_string()
+                            } else {
+                                format!("{v:.2}")
+                            }
+                        }),
+                )
+                .on_hover_text("Round off each turn, as a fraction of the segment length");
+                ui.add(
+                    egui::Slider::new(&mut view_rotation, 0.0..=360.0)
+                        .text("Rotation")
+                        .suffix("°"),
+                )
+                .on_hover_text("Turn the whole curve clockwise around the center of the view");
+                ui.horizontal(|ui| {
+                    ui.label("Background");
+                    let mut color = custom_background.unwrap_or(ui.visuals().panel_fill);
+                    if ui
+                        .add_enabled_ui(!transparent, |ui| ui.color_edit_button_srgba(&mut color))
+                        .inner
+                        .changed()
+                    {
+                        custom_background = Some(color);
+                    }
+                    ui.checkbox(&mut transparent, "Transparent")
+                        .on_hover_text("Leave the background empty, including in saved images");
+                });
+                let mut width = line_width.unwrap_or(ui.visuals().widgets.active.fg_stroke.width);
+                if ui
+                    .add(egui::Slider::new(&mut width, 0.5..=8.0).text("Line width"))
+                    .changed()
+                {
+                    line_width = Some(width);
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Stroke");
+                    ui.radio_value(&mut stroke_dash, StrokeDash::Solid, "Solid");
+                    ui.radio_value(&mut stroke_dash, StrokeDash::Dashed, "Dashed");
+                    ui.radio_value(&mut stroke_dash, StrokeDash::Dotted, "Dotted");
+                });
+                ui.checkbox(&mut constant_width, "Constant screen width")
+                    .on_hover_text("Keep lines the same thickness in pixels as the view is resized, instead of scaling them with the curve");
+                ui.checkbox(&mut show_attractor, "Show attractor");
+                ui.checkbox(&mut show_grid, "Show grid")
+                    .on_hover_text("Draw the lattice the vertices lie on, with the axes through the start of the curve");
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(&mut animate, "Animate")
+                        .on_hover_text("Draw the curve on a segment at a time, starting over once it's done")
+                        .changed()
+                    {
+                        anim_progress = 0.0;
+                    }
+                    ui.add_enabled(
+                        animate,
+                        egui::Slider::new(&mut anim_speed, 1.0..=100000.0)
+                            .logarithmic(true)
+                            .text("Segments/s"),
+                    );
+                });
+                ui.checkbox(&mut show_fps, "Show FPS");
+                // both of these write straight to the working directory, which the web build doesn't have
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button("Capture presets (P)").clicked() {
+                    export_status = Some(capture_status(background, ui.visuals(), line_width));
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    for scale in [1, 2, 4] {
+                        ui.radio_value(&mut png_scale, scale, format!("{scale}x"));
+                    }
+                    if ui
+                        .button("Save PNG")
+                        .on_hover_text("Render the curve at a multiple of its size, with the window's coloring, style, corners and rotation. Dashes, markers and fill are left out.")
+                        .clicked()
+                    {
+                        let visuals = ui.visuals();
+                        let px = (view_size * png_scale as f32).round().max(1.0) as u32;
+                        let style = raster::RasterStyle {
+                            render: render_style,
+                            corner_radius,
+                            rotation,
+                            width: view_width * png_scale as f32,
+                            background,
+                        };
+                        let transform = raster::transform(&curve, px, style);
+                        let mut mode = color_mode(
+                            coloring,
+                            gradient,
+                            pride_flag,
+                            &custom_stops,
+                            phase,
+                            visuals.widgets.active.fg_stroke.color,
+                            transform.origin.into(),
+                            curve.radius(transform.scale),
+                        );
+                        let path = format!("dragon_d{}_{png_scale}x.png", curve.depth());
+                        let image = raster::render(&curve, px, style, &mut *mode);
+                        export_status = Some(match image.save(&path) {
+                            Ok(()) => format!("Wrote {path}"),
+                            Err(err) => format!("Failed to save {path}: {err}"),
+                        });
+                    }
+                });
+                if ui
+                    .button("Save SVG")
+                    .on_hover_text("Write the curve as an SVG document, in the current coloring and stroke options")
+                    .clicked()
+                {
+                    let mut svg = String::new();
+                    let [r, g, b, _] = background.to_srgba_unmultiplied();
+                    let style = SvgStyle {
+                        render: render_style,
+                        background: (!transparent).then_some([r, g, b]),
+                        stroke_width: view_width,
+                        grid: show_grid,
+                        rotation: rotation.to_degrees(),
+                        dash: stroke_dash,
+                        markers: show_markers.then_some(marker_radius),
+                        ..SvgStyle::default()
+                    };
+                    let res = match coloring {
+                        // the solid colorings come out black
+                        Coloring::None | Coloring::WidthGradient => {
+                            curve.write_svg_styled(view_size, style, &mut svg)
+                        }
+                        _ => {
+                            let mut mode = color_mode(
+                                coloring,
+                                gradient,
+                                pride_flag,
+                                &custom_stops,
+                                phase,
+                                egui::Color32::BLACK,
+                                egui::Pos2::ZERO,
+                                curve.radius(curve.step(view_size)),
+                            );
+                            let total = curve.len();
+                            curve.write_svg_styled_colored(
+                                view_size,
+                                style,
+                                &mut |i, dir, (x, y)| {
+                                    let [r, g, b, _] = mode
+                                        .color(i, total, dir, egui::pos2(x, y))
+                                        .to_srgba_unmultiplied();
+                                    [r, g, b]
+                                },
+                                &mut svg,
+                            )
+                        }
+                    };
+                    let name = format!("dragon_d{}.svg", curve.depth());
+                    if res.is_err() {
+                        export_status = Some(format!("Failed to write {name}"));
+                    } else if let Some(status) = save_svg(&name, &svg) {
+                        export_status = Some(status);
+                    }
+                }
+                if let Some(status) = &export_status {
+                    ui.label(status);
+                }
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut show_indices, "Show indices");
+                    ui.add_enabled(
+                        show_indices,
+                        egui::Slider::new(&mut label_every, 1..=4096)
+                            .logarithmic(true)
+                            .text("Every"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut show_highlight, "Highlight segments");
+                    ui.add_enabled(
+                        show_highlight,
+                        egui::DragValue::new(&mut highlight_index)
+                            .range(0..=curve.len().saturating_sub(1))
+                            .prefix("from "),
+                    )
+                    .on_hover_text("Drag to scrub through the segments in order");
+                    ui.add_enabled(
+                        show_highlight,
+                        egui::DragValue::new(&mut highlight_count)
+                            .range(1..=curve.len())
+                            .suffix(" segments"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut show_markers, "Show endpoints")
+                        .on_hover_text("Mark the start of the curve in green and its end in red");
+                    ui.add_enabled(
+                        show_markers,
+                        egui::Slider::new(&mut marker_radius, 1.0..=16.0).text("Radius"),
+                    );
+                });
+                ui.checkbox(&mut hover_highlight, "Highlight under pointer")
+                    .on_hover_text("Mark the segment closest to the pointer and show its index");
+                if ui
+                    .button("Reset view (R)")
+                    .on_hover_text("Undo any zooming and panning, fitting the curve to the window")
+                    .clicked()
+                {
+                    pan = egui::Vec2::ZERO;
+                    zoom = 1.0;
+                }
+                if ui.button("Screensaver").clicked() {
+                    screensaver = true;
+                    saver_start = ui.input(|i| i.time);
+                    show = false;
+                }
             });
         }
-    });
-    if let Err(err) = res {
+    };
+    run(update);
+}
+
+/// Open a window and call `update` every frame until it's closed
+#[cfg(not(target_arch = "wasm32"))]
+fn run(update: impl FnMut(&egui::Context, &mut eframe::Frame) + 'static) {
+    if let Err(err) = eframe::run_simple_native("Dragon", Default::default(), update) {
         eprintln!("Failed to run app: {err}");
     }
 }
+
+/// Start drawing into the page's `dragon_canvas` and call `update` every frame
+#[cfg(target_arch = "wasm32")]
+fn run(update: impl FnMut(&egui::Context, &mut eframe::Frame) + 'static) {
+    use wasm_bindgen::JsCast;
+
+    /// App that just calls its update function, which is all `run_simple_native` does natively
+    struct SimpleApp<F>(F);
+    impl<F: FnMut(&egui::Context, &mut eframe::Frame)> eframe::App for SimpleApp<F> {
+        fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+            (self.0)(ctx, frame);
+        }
+    }
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let canvas = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.get_element_by_id("dragon_canvas"))
+            .and_then(|e| e.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+            .expect("the page should have a canvas with the id dragon_canvas");
+        let res = eframe::WebRunner::new()
+            .start(
+                canvas,
+                eframe::WebOptions::default(),
+                Box::new(|_| Ok(Box::new(SimpleApp(update)))),
+            )
+            .await;
+        if let Err(err) = res {
+            web_sys::console::error_1(&err);
+        }
+    });
+}
@@ -0,0 +1,176 @@
+//! Software rendering of curves into images, for exporting without going through the window
+
+use crate::ColorMode;
+use crate::dragon::{self, Dir, DragonCurve, RenderStyle, Transform};
+use eframe::egui;
+use image::{Rgba, RgbaImage};
+
+/// Draws anti-aliased segments into an image, colored the same way as on screen
+pub struct RasterDraw<'a> {
+    pub image: &'a mut RgbaImage,
+    pub pos: egui::Pos2,
+    /// Rotation applied to every segment
+    pub rot: egui::emath::Rot2,
+    pub width: f32,
+    pub mode: &'a mut dyn ColorMode,
+    /// Index of the segment being drawn
+    pub index: usize,
+    /// Number of segments in the curve
+    pub total: usize,
+    /// Direction of the segment being drawn
+    pub dir: Dir,
+}
+impl dragon::Draw for RasterDraw<'_> {
+    type Output = ();
+
+    fn line(&mut self, x: f32, y: f32) {
+        let old = self.pos;
+        self.pos += self.rot * egui::vec2(x, y);
+        let color = self
+            .mode
+            .color(self.index, self.total, self.dir, old.lerp(self.pos, 0.5));
+        stroke_segment(self.image, [old, self.pos], self.width, color);
+        self.index += 1;
+    }
+    fn move_by(&mut self, x: f32, y: f32) {
+        self.pos += self.rot * egui::vec2(x, y);
+    }
+    fn quad(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        // the same number of pieces as on screen, so the corners come out the same shape
+        const SAMPLES: usize = 8;
+        let start = self.pos;
+        let ctrl = self.rot * egui::vec2(cx, cy);
+        let end = self.rot * egui::vec2(x, y);
+        let color = self
+            .mode
+            .color(self.index, self.total, self.dir, start + ctrl);
+        let mut prev = start;
+        for i in 1..=SAMPLES {
+            let t = i as f32 / SAMPLES as f32;
+            let next = start + ctrl * (2.0 * t * (1.0 - t)) + end * (t * t);
+            stroke_segment(self.image, [prev, next], self.width, color);
+            prev = next;
+        }
+        self.pos = start + end;
+    }
+}
+impl RasterDraw<'_> {
+    /// Draw every segment of `curve` in the given style, starting from the current position.
+    ///
+    /// If `radius` is positive, corners are rounded off with that radius.
+    pub fn draw_curve(&mut self, curve: &DragonCurve, step: f32, style: RenderStyle, radius: f32) {
+        self.total = curve.len();
+        let mut prev = None;
+        let mut segs = curve.segments_styled(style).peekable();
+        while let Some(seg) = segs.next() {
+            self.dir = seg;
+            if radius > 0.0 {
+                seg.draw_rounded(prev, segs.peek().copied(), self, step, radius);
+            } else {
+                seg.draw(self, step);
+            }
+            prev = Some(seg);
+        }
+    }
+}
+
+/// How a curve is drawn by [`render`], besides its coloring
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterStyle {
+    pub render: RenderStyle,
+    /// Radius of the rounded corners, in segments, or 0 for sharp ones
+    pub corner_radius: f32,
+    /// Angle to turn the curve clockwise around the center of the image, in radians
+    pub rotation: f32,
+    pub width: f32,
+    pub background: egui::Color32,
+}
+
+/// Render `curve` into a new `size`-pixel square image, framed the same way as in the window.
+///
+/// `mode` is built for this framing, from `transform(curve, size, style)`.
+pub fn render(
+    curve: &DragonCurve,
+    size: u32,
+    style: RasterStyle,
+    mode: &mut dyn ColorMode,
+) -> RgbaImage {
+    let mut image =
+        RgbaImage::from_pixel(size, size, Rgba(style.background.to_srgba_unmultiplied()));
+    let transform = transform(curve, size, style);
+    RasterDraw {
+        image: &mut image,
+        pos: transform.origin.into(),
+        rot: egui::emath::Rot2::from_angle(style.rotation),
+        width: style.width,
+        mode,
+        index: 0,
+        total: 0,
+        dir: Dir::Np0,
+    }
+    .draw_curve(
+        curve,
+        transform.scale,
+        style.render,
+        style.corner_radius * transform.scale,
+    );
+    image
+}
+
+/// Placement of `curve` rendered into a `size`-pixel image
+pub fn transform(curve: &DragonCurve, size: u32, style: RasterStyle) -> Transform {
+    let half = size as f32 * 0.5;
+    let angle = crate::frame_angle(curve, style.render, style.rotation);
+    Transform::framed(curve, (half, half), size as f32, angle)
+}
+
+/// Blend a line of the given width between two points into the image, with coverage falling
+/// off over the pixel at its edge
+fn stroke_segment(
+    image: &mut RgbaImage,
+    [a, b]: [egui::Pos2; 2],
+    width: f32,
+    color: egui::Color32,
+) {
+    let [r, g, bl, alpha] = color.to_srgba_unmultiplied();
+    let alpha = alpha as f32 / 255.0;
+    let half = width * 0.5;
+    let reach = half + 1.0;
+    let min = a.min(b) - egui::Vec2::splat(reach);
+    let max = a.max(b) + egui::Vec2::splat(reach);
+    let x0 = min.x.floor().max(0.0) as u32;
+    let y0 = min.y.floor().max(0.0) as u32;
+    let x1 = (max.x.ceil() as u32).min(image.width());
+    let y1 = (max.y.ceil() as u32).min(image.height());
+    let seg = b - a;
+    let len2 = seg.length_sq();
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let p = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+            let t = if len2 > 0.0 {
+                ((p - a).dot(seg) / len2).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let dist = p.distance(a + seg * t);
+            let coverage = (half + 0.5 - dist).clamp(0.0, 1.0) * alpha;
+            if coverage <= 0.0 {
+                continue;
+            }
+            let px = image.get_pixel_mut(x, y);
+            // composite over what's there, weighting it by its own alpha so that lines over a
+            // transparent background don't pick up its color at their edges
+            let under = px[3] as f32 / 255.0 * (1.0 - coverage);
+            let out_alpha = coverage + under;
+            let blend = |src: u8, dst: u8| {
+                ((src as f32 * coverage + dst as f32 * under) / out_alpha).round() as u8
+            };
+            *px = Rgba([
+                blend(r, px[0]),
+                blend(g, px[1]),
+                blend(bl, px[2]),
+                (out_alpha * 255.0).round() as u8,
+            ]);
+        }
+    }
+}
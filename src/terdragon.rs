@@ -0,0 +1,169 @@
+//! The terdragon, which folds each segment into three with 120° turns.
+//!
+//! Its turns don't fit the 45° directions of [`Dir`](crate::dragon::Dir), so it has its own
+//! direction type in 30° steps, while still drawing through the same [`Draw`] trait. It can be
+//! written out with the `--terdragon` command-line option.
+
+use crate::dragon::{Draw, Num, Pen, SVG_PRECISION, SvgPath};
+use std::fmt::{self, Write};
+
+/// Deepest terdragon with at most `budget` segments, like
+/// [`max_depth`](crate::dragon::max_depth) for the dragon
+pub fn max_depth(budget: usize) -> u8 {
+    budget.max(1).ilog(3) as u8
+}
+
+/// Direction of a terdragon segment in 30° steps, counterclockwise on screen from `+x`.
+///
+/// The true directions only ever differ by 60°, but every fold turns the whole curve by 30°, so
+/// the in-between directions are needed to keep the endpoints in place between depths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TerDir(u8);
+impl TerDir {
+    /// Pointing along `+x`
+    pub const EAST: Self = Self(0);
+
+    /// Direction `steps` 30° steps counterclockwise from `+x`, wrapping around
+    pub const fn new(steps: u8) -> Self {
+        Self(steps % 12)
+    }
+    /// Rotate counterclockwise by `by` 30° steps
+    pub const fn rotate(self, by: u8) -> Self {
+        Self::new(self.0 + by % 12)
+    }
+    /// Angle counterclockwise from `+x`, in radians
+    pub fn angle(self) -> f32 {
+        self.0 as f32 * std::f32::consts::FRAC_PI_6
+    }
+    pub fn draw<D: Draw + ?Sized>(self, out: &mut D, len: f32) -> D::Output {
+        match self.0 {
+            0 => out.horiz(len),
+            3 => out.vert(-len),
+            6 => out.horiz(-len),
+            9 => out.vert(len),
+            // y increases downwards, so counterclockwise goes towards -y
+            _ => {
+                let (sin, cos) = self.angle().sin_cos();
+                out.line(len * cos, len * -sin)
+            }
+        }
+    }
+}
+
+/// A terdragon curve, with `3^depth` segments.
+///
+/// Like [`DragonCurve`](crate::dragon::DragonCurve), the endpoints stay in place as the depth
+/// changes, so the segments shrink by a factor of `√3` at each depth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Terdragon {
+    list: Vec<TerDir>,
+    depth: u8,
+}
+impl Terdragon {
+    pub fn new(start: TerDir) -> Self {
+        Self {
+            list: vec![start],
+            depth: 0,
+        }
+    }
+    /// Change the depth, folding every segment into three for each level deeper.
+    ///
+    /// Each fold replaces a segment with one turned 30° clockwise, one turned 120° back from that,
+    /// and a third parallel to the first. Segments grow as `3^depth`, so depths much past 12 take
+    /// a lot of memory.
+    pub fn set_depth(&mut self, depth: u8) {
+        if depth > self.depth {
+            let mut next = Vec::with_capacity(3usize.pow(depth as u32));
+            for _ in self.depth..depth {
+                for &dir in &self.list {
+                    let first = dir.rotate(11);
+                    next.extend([first, first.rotate(4), first]);
+                }
+                std::mem::swap(&mut self.list, &mut next);
+                next.clear();
+            }
+        } else if depth < self.depth {
+            // the first third of a curve is the curve one level shallower, turned clockwise
+            let rot = (self.depth - depth) % 12;
+            self.list.truncate(3usize.pow(depth as u32));
+            for dir in &mut self.list {
+                *dir = dir.rotate(rot);
+            }
+        }
+        self.depth = depth;
+    }
+    /// Directions of the segments in order, from the start of the curve to its end
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = TerDir> + ExactSizeIterator + '_ {
+        self.list.iter().copied()
+    }
+    /// Length of a single segment when the curve is drawn in a `size`-sized square, with its
+    /// endpoints at the same places as a [`DragonCurve`](crate::dragon::DragonCurve)
+    pub fn step(&self, size: f32) -> f32 {
+        size * 0.5 / 3f32.sqrt().powi(self.depth as i32)
+    }
+    /// Bounding box of the curve with segments of length `step`, relative to its starting point,
+    /// as `(min_x, min_y, max_x, max_y)`
+    pub fn bounds(&self, step: f32) -> (f32, f32, f32, f32) {
+        let mut pen = Pen { x: 0.0, y: 0.0 };
+        let mut bounds = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for dir in self.segments() {
+            dir.draw(&mut pen, step);
+            bounds.0 = bounds.0.min(pen.x);
+            bounds.1 = bounds.1.min(pen.y);
+            bounds.2 = bounds.2.max(pen.x);
+            bounds.3 = bounds.3.max(pen.y);
+        }
+        bounds
+    }
+    /// Write the curve as an SVG document with a `viewBox` fitting its bounds, the same way as
+    /// [`DragonCurve::write_svg`](crate::dragon::DragonCurve::write_svg)
+    pub fn write_svg(&self, size: f32, w: &mut dyn Write) -> fmt::Result {
+        let step = self.step(size);
+        let (min_x, min_y, max_x, max_y) = self.bounds(step);
+        let n = |v: f32| Num(v, Some(SVG_PRECISION));
+        let (width, height) = (n(max_x - min_x + 1.0), n(max_y - min_y + 1.0));
+        write!(
+            w,
+            r#"<svg width="{width}" height="{height}" viewBox="{} {} {width} {height}" xmlns="http://www.w3.org/2000/svg"><path style="stroke:black;stroke-width:1;fill:none" d="M0 0"#,
+            n(min_x - 0.5),
+            n(min_y - 0.5),
+        )?;
+        let mut path = SvgPath {
+            writer: w,
+            precision: Some(SVG_PRECISION),
+        };
+        for dir in self.segments() {
+            dir.draw(&mut path, step)?;
+        }
+        w.write_str(r#""/></svg>"#)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_stay_put() {
+        let mut curve = Terdragon::new(TerDir::EAST);
+        for depth in (0..=6).chain((0..6).rev()) {
+            curve.set_depth(depth);
+            assert_eq!(curve.segments().len(), 3usize.pow(depth as u32));
+            let mut pen = Pen { x: 0.0, y: 0.0 };
+            for dir in curve.segments() {
+                dir.draw(&mut pen, curve.step(100.0));
+            }
+            assert!(
+                (pen.x - 50.0).abs() < 1e-3 && pen.y.abs() < 1e-3,
+                "depth {depth}"
+            );
+        }
+    }
+
+    #[test]
+    fn max_depth_fits_budget() {
+        assert_eq!(max_depth(1 << 16), 10);
+        assert_eq!(max_depth(3usize.pow(7)), 7);
+        assert_eq!(max_depth(3usize.pow(7) - 1), 6);
+    }
+}